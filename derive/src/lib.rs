@@ -0,0 +1,320 @@
+//! Derive macros for [`indenter`](https://docs.rs/indenter)
+//!
+//! This crate is re-exported by `indenter` with the `derive` feature, and is not meant to be
+//! depended on directly.
+#![warn(
+    missing_debug_implementations,
+    missing_docs,
+    rust_2018_idioms,
+    unreachable_pub,
+    unused_qualifications
+)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Fields, LitStr, Result};
+
+/// Derive `Display` by writing every field on a line of its own
+///
+/// # Explanation
+///
+/// Each field of a struct is written as `name: value`, with the index as the name for tuple
+/// structs, and fields are separated by newlines. An enum writes the name of its variant, with
+/// the fields of the variant indented one level below it, and unit structs write their name.
+/// The values are written with their own `Display` impls, so fields that derive it too can be
+/// nested.
+///
+/// The following attributes are supported:
+///
+/// - `#[indented(skip)]` on a field leaves it out.
+/// - `#[indented(nested)]` on a field writes its name on a line of its own and the value below
+///   it, indented one level, for values that span several lines.
+/// - `#[indented(prefix = "...")]` sets the indentation of one level, `DEFAULT_INDENTATION` by
+///   default. On the type it applies to every nested field and the fields of every variant, and
+///   on a field to that field only.
+///
+/// ```rust
+/// use indenter::IndentedDisplay;
+///
+/// #[derive(IndentedDisplay)]
+/// struct Server {
+///     host: &'static str,
+///     port: u16,
+/// }
+///
+/// #[derive(IndentedDisplay)]
+/// #[indented(prefix = "  ")]
+/// struct Config {
+///     name: &'static str,
+///     #[indented(nested)]
+///     server: Server,
+///     #[indented(skip)]
+///     token: &'static str,
+/// }
+///
+/// let config = Config {
+///     name: "api",
+///     server: Server {
+///         host: "localhost",
+///         port: 8080,
+///     },
+///     token: "secret",
+/// };
+///
+/// assert_eq!(
+///     config.to_string(),
+///     "name: api\nserver:\n  host: localhost\n  port: 8080"
+/// );
+/// ```
+#[proc_macro_derive(IndentedDisplay, attributes(indented))]
+pub fn derive_indented_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+/// Derive a `report` method for an error type that displays it with its sources
+///
+/// # Explanation
+///
+/// The method returns an `indenter::ErrorReport`, which writes the error followed by every error
+/// in its `source` chain, numbered and indented under a `Caused by:` heading. This works with any
+/// type that implements `std::error::Error`, such as one derived with `thiserror`, and needs the
+/// `std` feature of `indenter` as well.
+///
+/// ```rust
+/// use std::{error::Error, fmt};
+/// use indenter::IndentedReport;
+///
+/// #[derive(Debug, IndentedReport)]
+/// enum ConfigError {
+///     Parse(std::num::ParseIntError),
+/// }
+///
+/// impl fmt::Display for ConfigError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str("invalid port")
+///     }
+/// }
+///
+/// impl Error for ConfigError {
+///     fn source(&self) -> Option<&(dyn Error + 'static)> {
+///         match self {
+///             ConfigError::Parse(error) => Some(error),
+///         }
+///     }
+/// }
+///
+/// let error = ConfigError::Parse("80a".parse::<u16>().unwrap_err());
+///
+/// assert_eq!(
+///     error.report().to_string(),
+///     "invalid port\n\nCaused by:\n   0: invalid digit found in string"
+/// );
+/// ```
+#[proc_macro_derive(IndentedReport)]
+pub fn derive_indented_report(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns a report that displays this error followed by its sources
+            pub fn report(&self) -> ::indenter::ErrorReport<'_>
+            where
+                Self: ::std::error::Error + 'static,
+            {
+                ::indenter::ErrorReport::new(self)
+            }
+        }
+    }
+    .into()
+}
+
+/// The options of an `#[indented(...)]` attribute
+#[derive(Default)]
+struct Options {
+    skip: bool,
+    nested: bool,
+    prefix: Option<LitStr>,
+}
+
+impl Options {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = Options::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("indented")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    options.skip = true;
+                } else if meta.path.is_ident("nested") {
+                    options.nested = true;
+                } else if meta.path.is_ident("prefix") {
+                    options.prefix = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("expected `skip`, `nested` or `prefix`"));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let options = Options::parse(&input.attrs)?;
+    if options.skip || options.nested {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`skip` and `nested` can only be used on fields",
+        ));
+    }
+    let prefix = match options.prefix {
+        Some(prefix) => quote!(#prefix),
+        None => quote!(::indenter::DEFAULT_INDENTATION),
+    };
+
+    let body = match &input.data {
+        Data::Struct(data) if data.fields.is_empty() => {
+            let label = input.ident.to_string();
+            quote!(f.write_str(#label))
+        }
+        Data::Struct(data) => {
+            let (pattern, writes) = fields(&data.fields, &prefix, "")?;
+            if writes.is_empty() {
+                return Ok(display(&input, quote!(Ok(()))));
+            }
+
+            quote! {
+                let Self #pattern = self;
+                let __writer = f;
+                #writes
+                Ok(())
+            }
+        }
+        Data::Enum(data) if data.variants.is_empty() => quote!(match *self {}),
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let name = &variant.ident;
+                let label = name.to_string();
+                let (pattern, writes) = fields(&variant.fields, &prefix, "\n")?;
+                if writes.is_empty() {
+                    arms.push(quote!(Self::#name { .. } => f.write_str(#label),));
+                    continue;
+                }
+
+                arms.push(quote! {
+                    Self::#name #pattern => {
+                        f.write_str(#label)?;
+                        let __writer = &mut ::indenter::indented(f).with_str(#prefix);
+                        #writes
+                        Ok(())
+                    }
+                });
+            }
+
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "`IndentedDisplay` can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(display(&input, body))
+}
+
+/// Returns the `Display` impl for `input` that runs `body`
+fn display(input: &DeriveInput, body: TokenStream2) -> TokenStream2 {
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::core::fmt::Display));
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    }
+}
+
+/// Returns the pattern that binds `fields`, and the code that writes them to `__writer`
+///
+/// `separator` is written before the first field, and a newline before every other.
+fn fields(
+    fields: &Fields,
+    prefix: &TokenStream2,
+    mut separator: &str,
+) -> Result<(TokenStream2, TokenStream2)> {
+    let mut bindings = Vec::new();
+    let mut writes = Vec::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        let options = Options::parse(&field.attrs)?;
+        let label = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => index.to_string(),
+        };
+
+        if options.skip {
+            bindings.push(match &field.ident {
+                Some(ident) => quote!(#ident: _),
+                None => quote!(_),
+            });
+            continue;
+        }
+
+        let binding = match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("__field{}", index),
+        };
+        bindings.push(quote!(#binding));
+
+        writes.push(if options.nested {
+            let prefix = match &options.prefix {
+                Some(prefix) => quote!(#prefix),
+                None => prefix.clone(),
+            };
+            quote! {
+                ::core::fmt::Write::write_fmt(
+                    &mut *__writer,
+                    ::core::format_args!("{}{}:\n", #separator, #label),
+                )?;
+                ::core::fmt::Write::write_fmt(
+                    &mut ::indenter::indented(&mut *__writer).with_str(#prefix),
+                    ::core::format_args!("{}", #binding),
+                )?;
+            }
+        } else {
+            quote! {
+                ::core::fmt::Write::write_fmt(
+                    &mut *__writer,
+                    ::core::format_args!("{}{}: {}", #separator, #label, #binding),
+                )?;
+            }
+        });
+        separator = "\n";
+    }
+
+    let pattern = match fields {
+        Fields::Named(_) => quote!({ #(#bindings,)* }),
+        Fields::Unnamed(_) => quote!(( #(#bindings,)* )),
+        Fields::Unit => quote!(),
+    };
+
+    Ok((pattern, quote!(#(#writes)*)))
+}
@@ -0,0 +1,34 @@
+use indenter::IndentedDisplay;
+
+#[derive(IndentedDisplay)]
+struct Point(i32, #[indented(skip)] i32, i32);
+
+#[derive(IndentedDisplay)]
+struct Marker;
+
+#[derive(IndentedDisplay)]
+#[indented(prefix = "| ")]
+enum Event<T> {
+    Started,
+    Moved {
+        #[indented(nested, prefix = "  ")]
+        to: Point,
+        by: T,
+    },
+    Done(#[indented(skip)] T),
+}
+
+#[test]
+fn enum_variants() {
+    let to = Point(1, 2, 3);
+    assert_eq!(to.1, 2);
+    let moved = Event::Moved { to, by: "user" };
+
+    assert_eq!(Marker.to_string(), "Marker");
+    assert_eq!(Event::<u8>::Started.to_string(), "Started");
+    assert_eq!(Event::Done(1).to_string(), "Done");
+    assert_eq!(
+        moved.to_string(),
+        "Moved\n| to:\n|   0: 1\n|   2: 3\n| by: user"
+    );
+}
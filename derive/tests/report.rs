@@ -0,0 +1,40 @@
+use indenter::IndentedReport;
+use std::{error::Error, fmt};
+
+#[derive(Debug, IndentedReport)]
+struct Wrapped<E> {
+    context: &'static str,
+    source: E,
+}
+
+impl<E: fmt::Display> fmt::Display for Wrapped<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.context)
+    }
+}
+
+impl<E: Error + 'static> Error for Wrapped<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[test]
+fn generic_chain() {
+    let error = Wrapped {
+        context: "loading settings",
+        source: Wrapped {
+            context: "reading line 3\nof settings.toml",
+            source: fmt::Error,
+        },
+    };
+
+    assert_eq!(
+        error.report().to_string(),
+        concat!(
+            "loading settings\n\nCaused by:\n",
+            "   0: reading line 3\n      of settings.toml\n",
+            "   1: an error occurred when formatting an argument",
+        )
+    );
+}
@@ -0,0 +1,120 @@
+use crate::{indented, Format, TrailingNewline};
+use core::fmt::{self, Write};
+
+/// Asserts that two multi-line strings are equal, reporting mismatches line by line
+///
+/// On mismatch the panic message contains both sides rendered with a numbered gutter and a `>`
+/// marker in front of the first line that differs, which is far easier to read than the single
+/// escaped string printed by `assert_eq!`.
+///
+/// ```rust
+/// use indenter::assert_lines_eq;
+///
+/// assert_lines_eq!(format!("{}\n{}", "a", "b"), "a\nb");
+/// ```
+#[macro_export]
+macro_rules! assert_lines_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_lines_eq(
+            ::core::convert::AsRef::<str>::as_ref(&$left),
+            ::core::convert::AsRef::<str>::as_ref(&$right),
+        )
+    };
+}
+
+/// Asserts that two multi-line strings are equal, reporting mismatches line by line
+///
+/// This is the function behind the `assert_lines_eq!` macro.
+///
+/// ```rust,should_panic
+/// indenter::assert_lines_eq("a\nb\nc", "a\nB\nc");
+/// ```
+#[track_caller]
+pub fn assert_lines_eq(left: &str, right: &str) {
+    if left == right {
+        return;
+    }
+
+    let line = first_difference(left, right);
+    panic!(
+        "assertion failed: lines differ starting at line {}\nleft:\n{}\nright:\n{}",
+        line + 1,
+        Numbered { text: left, line },
+        Numbered { text: right, line },
+    );
+}
+
+fn first_difference(left: &str, right: &str) -> usize {
+    let mut left_lines = left.split('\n');
+    let mut right_lines = right.split('\n');
+    let mut line = 0;
+
+    loop {
+        match (left_lines.next(), right_lines.next()) {
+            (None, None) => return line,
+            (left, right) if left == right => line += 1,
+            _ => return line,
+        }
+    }
+}
+
+struct Numbered<'a> {
+    text: &'a str,
+    line: usize,
+}
+
+impl fmt::Display for Numbered<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marked = self.line;
+        let mut gutter = move |line, f: &mut dyn Write| {
+            let marker = if line == marked { '>' } else { ' ' };
+            write!(f, "{} {: >4} | ", marker, line + 1)
+        };
+
+        // Blank lines, including one after a trailing newline, are numbered and marked too, since
+        // they are often where the difference is
+        let mut indented = indented(f)
+            .with_format(Format::Custom {
+                inserter: &mut gutter,
+            })
+            .skip_empty(false)
+            .trailing_newline(TrailingNewline::Indent);
+        indented.write_str(self.text)?;
+        indented.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal() {
+        assert_lines_eq!(String::from("a\n  b\n"), "a\n  b\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "left:\n     1 | a\n>    2 | b\nright:\n     1 | a\n>    2 | c")]
+    fn marks_first_difference() {
+        assert_lines_eq("a\nb", "a\nc");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "left:\n     1 | a\n>    2 | \n     3 | c\nright:\n     1 | a\n>    2 | b\n     3 | c"
+    )]
+    fn marks_blank_line() {
+        assert_lines_eq("a\n\nc", "a\nb\nc");
+    }
+
+    #[test]
+    #[should_panic(expected = "left:\n     1 | a\n>    2 | \nright:\n     1 | a")]
+    fn marks_trailing_newline() {
+        assert_lines_eq("a\n", "a");
+    }
+
+    #[test]
+    fn difference_in_length() {
+        assert_eq!(first_difference("a\nb", "a\nb\n"), 2);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::buffer::StackBuffer;
+use core::fmt::{self, Write};
+
+/// A writer that indents every line after the first by the leading whitespace of the first line
+///
+/// # Explanation
+///
+/// Editors call this auto-indent: a new line starts at the same depth as the one before it. The
+/// first line is written as it is, and its leading whitespace, spaces and tabs exactly as they
+/// were, is captured and written before every later line. Blank lines are left empty. To continue
+/// beneath a line that was written some other way, `continuing` takes the indentation from that
+/// line instead, in which case the first line is indented too. Up to 64 bytes of whitespace are
+/// captured, which is more than any reasonable indentation.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::auto_indent;
+///
+/// let mut output = String::new();
+/// write!(auto_indent(&mut output), "\t  let x = [\n1,\n2,\n];")?;
+///
+/// assert_eq!(output, "\t  let x = [\n\t  1,\n\t  2,\n\t  ];");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct AutoIndent<'a, W: ?Sized> {
+    inner: &'a mut W,
+    prefix: StackBuffer,
+    /// Whether the leading whitespace of the first line is still being captured
+    capturing: bool,
+    needs_indent: bool,
+}
+
+/// Helper function for creating a writer that indents like the first line written through it
+pub fn auto_indent<W: ?Sized>(inner: &mut W) -> AutoIndent<'_, W> {
+    AutoIndent {
+        inner,
+        prefix: StackBuffer::new(),
+        capturing: true,
+        needs_indent: false,
+    }
+}
+
+impl<W: ?Sized> AutoIndent<'_, W> {
+    /// Indent every line, the first one included, by the leading whitespace of `line`
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::auto_indent;
+    ///
+    /// let previous = "    - item";
+    /// let mut output = String::new();
+    /// write!(auto_indent(&mut output).continuing(previous), "- next\n- last")?;
+    ///
+    /// assert_eq!(output, "    - next\n    - last");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn continuing(mut self, line: &str) -> Self {
+        self.prefix = StackBuffer::new();
+        let whitespace = line.len() - line.trim_start_matches([' ', '\t']).len();
+        self.capture(&line[..whitespace]);
+        self.capturing = false;
+        self.needs_indent = true;
+        self
+    }
+
+    /// Returns the indentation captured so far
+    pub fn indentation(&self) -> &str {
+        self.prefix.as_str()
+    }
+
+    /// Add `whitespace` to the captured indentation, as much of it as fits
+    fn capture(&mut self, whitespace: &str) {
+        let room = StackBuffer::CAPACITY - self.prefix.len();
+        let _ = self
+            .prefix
+            .write_str(&whitespace[..whitespace.len().min(room)]);
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for AutoIndent<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (ind, line) in s.split('\n').enumerate() {
+            if ind > 0 {
+                self.inner.write_char('\n')?;
+                self.capturing = false;
+                self.needs_indent = true;
+            }
+
+            if self.capturing {
+                let whitespace = line.len() - line.trim_start_matches([' ', '\t']).len();
+                self.capture(&line[..whitespace]);
+                self.capturing = whitespace == line.len();
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if self.needs_indent {
+                self.inner.write_str(self.prefix.as_str())?;
+                self.needs_indent = false;
+            }
+            self.inner.write_str(line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn whitespace_split_across_writes() {
+        let mut output = String::new();
+        let mut auto = auto_indent(&mut output);
+        auto.write_str("  ").unwrap();
+        auto.write_str("\tif x {\n").unwrap();
+        assert_eq!(auto.indentation(), "  \t");
+
+        auto.write_str("\nbody   ").unwrap();
+        assert_eq!(auto.indentation(), "  \t");
+        assert_eq!(output, "  \tif x {\n\n  \tbody   ");
+    }
+}
@@ -0,0 +1,218 @@
+use crate::spaces::write_spaces;
+use crate::width::{display_width, Column};
+use core::fmt;
+
+/// The characters a `Boxed` draws its border with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Border {
+    /// Box drawing characters, such as `┌─┐`, the default
+    #[default]
+    Unicode,
+    /// Plain ASCII, such as `+-+`, for terminals and logs that can't show box drawing characters
+    Ascii,
+}
+
+impl Border {
+    /// Returns the corners, from the top left clockwise, followed by the horizontal and vertical
+    /// edges
+    fn chars(self) -> [char; 6] {
+        match self {
+            Border::Unicode => ['┌', '┐', '┘', '└', '─', '│'],
+            Border::Ascii => ['+', '+', '+', '+', '-', '|'],
+        }
+    }
+}
+
+/// A writer that draws a box around everything written through it
+///
+/// # Explanation
+///
+/// Grouping the parts of an error report visually, such as the context of a failure, reads
+/// better with a frame around each part. The box is `width` columns wide on the inside, plus
+/// `padding` blank columns on either side of the content, and every line is filled with spaces up
+/// to the right border, measured with `display_width`. Lines wider than `width` push the right
+/// border out on their line. An optional title is written into the top border. The top border is
+/// written with the first line, and the bottom border by `finish`, which has to be called once
+/// the content is complete. A trailing newline in the content doesn't add an empty row.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{boxed, indented};
+///
+/// let mut output = String::new();
+/// let mut frame = boxed(&mut output, 12).with_title("Context");
+/// write!(indented(&mut frame).with_str("- "), "retries: 3\ndelay: 5s")?;
+/// frame.finish()?;
+///
+/// assert_eq!(
+///     output,
+///     "\
+/// ┌─ Context ────┐
+/// │ - retries: 3 │
+/// │ - delay: 5s  │
+/// └──────────────┘"
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct Boxed<'a, W: ?Sized> {
+    inner: &'a mut W,
+    width: usize,
+    padding: usize,
+    title: Option<&'a str>,
+    border: Border,
+    column: Column,
+    /// Whether the top border has been written
+    opened: bool,
+    /// Whether the left border of the current line has been written
+    in_line: bool,
+}
+
+/// Helper function for creating a writer that draws a box `width` columns wide around its output
+pub fn boxed<W: ?Sized>(inner: &mut W, width: usize) -> Boxed<'_, W> {
+    Boxed {
+        inner,
+        width,
+        padding: 1,
+        title: None,
+        border: Border::Unicode,
+        column: Column::default(),
+        opened: false,
+        in_line: false,
+    }
+}
+
+impl<'a, W: ?Sized> Boxed<'a, W> {
+    /// Sets the title written into the top border
+    pub fn with_title(mut self, title: &'a str) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    /// Sets the number of blank columns between the borders and the content, 1 by default
+    pub fn with_padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the characters the border is drawn with
+    pub fn with_border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+}
+
+impl<W: fmt::Write + ?Sized> Boxed<'_, W> {
+    /// Close the last line and draw the bottom border
+    pub fn finish(&mut self) -> fmt::Result {
+        self.open()?;
+        if self.in_line {
+            self.end_line()?;
+            self.inner.write_char('\n')?;
+        }
+
+        let [.., bottom_right, bottom_left, horizontal, _] = self.border.chars();
+        self.inner.write_char(bottom_left)?;
+        self.edge(horizontal, self.width + 2 * self.padding)?;
+        self.inner.write_char(bottom_right)
+    }
+
+    /// Write the top border, if it hasn't been yet
+    fn open(&mut self) -> fmt::Result {
+        if self.opened {
+            return Ok(());
+        }
+        self.opened = true;
+
+        let [top_left, top_right, .., horizontal, _] = self.border.chars();
+        let mut inside = self.width + 2 * self.padding;
+        self.inner.write_char(top_left)?;
+        if let Some(title) = self.title {
+            write!(self.inner, "{} {} ", horizontal, title)?;
+            inside = inside.saturating_sub(display_width(title) + 3);
+        }
+        self.edge(horizontal, inside)?;
+        self.inner.write_char(top_right)?;
+        self.inner.write_char('\n')
+    }
+
+    /// Write the left border and padding of a line
+    fn start_line(&mut self) -> fmt::Result {
+        self.open()?;
+        self.inner.write_char(self.border.chars()[5])?;
+        write_spaces(self.inner, self.padding)?;
+        self.in_line = true;
+        Ok(())
+    }
+
+    /// Fill the current line up to the right border and write it
+    fn end_line(&mut self) -> fmt::Result {
+        let fill = self.width.saturating_sub(self.column.width) + self.padding;
+        write_spaces(self.inner, fill)?;
+        self.inner.write_char(self.border.chars()[5])?;
+        self.column = Column::default();
+        self.in_line = false;
+        Ok(())
+    }
+
+    /// Write `count` copies of the horizontal edge
+    fn edge(&mut self, horizontal: char, count: usize) -> fmt::Result {
+        for _ in 0..count {
+            self.inner.write_char(horizontal)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Boxed<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (ind, line) in s.split('\n').enumerate() {
+            if ind > 0 {
+                if !self.in_line {
+                    self.start_line()?;
+                }
+                self.end_line()?;
+                self.inner.write_char('\n')?;
+            }
+
+            if !line.is_empty() {
+                if !self.in_line {
+                    self.start_line()?;
+                }
+                self.inner.write_str(line)?;
+                self.column.advance(line);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::String;
+    use fmt::Write;
+
+    #[test]
+    fn ascii_with_blank_lines() {
+        let mut output = String::new();
+        let mut frame = boxed(&mut output, 3)
+            .with_border(Border::Ascii)
+            .with_padding(0);
+        frame.write_str("a\n\nb\n").unwrap();
+        frame.finish().unwrap();
+
+        assert_eq!(output, "+---+\n|a  |\n|   |\n|b  |\n+---+");
+    }
+
+    #[test]
+    fn empty_box() {
+        let mut output = String::new();
+        boxed(&mut output, 2).finish().unwrap();
+
+        assert_eq!(output, "┌────┐\n└────┘");
+    }
+}
@@ -0,0 +1,60 @@
+use core::fmt;
+
+/// A fixed capacity string buffer on the stack
+///
+/// Writing more than `CAPACITY` bytes fails with `fmt::Error` and leaves the contents written
+/// before the failing call in place.
+#[derive(Clone, Copy)]
+pub(crate) struct StackBuffer {
+    buf: [u8; StackBuffer::CAPACITY],
+    len: usize,
+}
+
+impl StackBuffer {
+    /// The number of bytes a buffer can hold
+    pub(crate) const CAPACITY: usize = 64;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            buf: [0; Self::CAPACITY],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // Only whole `str`s are ever copied in, so this cannot fail
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl fmt::Write for StackBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn overflow_is_an_error() {
+        let mut buf = StackBuffer::new();
+        buf.write_str("ab").unwrap();
+
+        let long = [b'x'; StackBuffer::CAPACITY];
+        assert!(buf.write_str(core::str::from_utf8(&long).unwrap()).is_err());
+        assert_eq!(buf.as_str(), "ab");
+    }
+}
@@ -0,0 +1,157 @@
+use crate::indented;
+use core::fmt::{self, Write};
+
+/// The severity of a `Callout`, which decides its label and color by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// `error:`, in bold red
+    Error,
+    /// `warning:`, in bold yellow
+    Warning,
+    /// `note:`, in bold green
+    Note,
+    /// `help:`, in bold cyan
+    Help,
+}
+
+impl Severity {
+    /// Returns the label written in the header, without the colon
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+            Severity::Help => "help",
+        }
+    }
+
+    /// Returns the ANSI escape sequence the label and gutter are styled with
+    pub fn style(self) -> &'static str {
+        match self {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+            Severity::Note => "\x1b[1;32m",
+            Severity::Help => "\x1b[1;36m",
+        }
+    }
+}
+
+/// Helper struct for displaying a `warning:` or `note:` style callout block
+///
+/// # Explanation
+///
+/// A callout starts with a header line of the label of its severity, followed by a title, and
+/// sets its body apart with a vertical bar in front of every line, blank ones included. The label
+/// and the bar are colored after the severity, which `color(false)` turns off, for example with
+/// `ColorChoice::enabled` from the `std` feature. The label, style and bar can each be replaced,
+/// for severities of your own.
+///
+/// ```rust
+/// use indenter::{Callout, Severity};
+///
+/// let callout = Callout::new(Severity::Warning, "`retries` was renamed\n\nuse `max_retries`")
+///     .with_title("deprecated config key")
+///     .color(false);
+///
+/// assert_eq!(
+///     callout.to_string(),
+///     "warning: deprecated config key\n| `retries` was renamed\n| \n| use `max_retries`"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Callout<'a, B> {
+    label: &'a str,
+    style: &'a str,
+    title: Option<&'a str>,
+    gutter: &'static str,
+    color: bool,
+    body: B,
+}
+
+impl<'a, B: fmt::Display> Callout<'a, B> {
+    /// Construct a callout with the label and style of `severity`
+    pub fn new(severity: Severity, body: B) -> Self {
+        Self {
+            label: severity.label(),
+            style: severity.style(),
+            title: None,
+            gutter: "| ",
+            color: true,
+            body,
+        }
+    }
+
+    /// Sets the title written after the label
+    pub fn with_title(mut self, title: &'a str) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    /// Sets the label written in the header, without the colon
+    pub fn with_label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Sets the ANSI escape sequence the label and gutter are styled with
+    pub fn with_style(mut self, style: &'a str) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the gutter in front of every line of the body, `"| "` by default
+    pub fn with_gutter(mut self, gutter: &'static str) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    /// Sets whether the label and gutter are styled
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+}
+
+impl<B: fmt::Display> fmt::Display for Callout<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.color {
+            write!(f, "{}{}\x1b[0m:", self.style, self.label)?;
+        } else {
+            write!(f, "{}:", self.label)?;
+        }
+        if let Some(title) = self.title {
+            write!(f, " {}", title)?;
+        }
+        f.write_char('\n')?;
+
+        let style = self.style;
+        let mut stylize = |gutter: &str, f: &mut dyn Write| write!(f, "{}{}\x1b[0m", style, gutter);
+
+        write!(
+            indented(f)
+                .with_str(self.gutter)
+                .skip_empty(false)
+                .stylize_gutter(&mut stylize)
+                .color(self.color),
+            "{}",
+            self.body
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn styled_gutter() {
+        let callout = Callout::new(Severity::Note, "a\nb").with_gutter("│ ");
+
+        assert_eq!(
+            callout.to_string(),
+            "\x1b[1;32mnote\x1b[0m:\n\x1b[1;32m│ \x1b[0ma\n\x1b[1;32m│ \x1b[0mb"
+        );
+    }
+}
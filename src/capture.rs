@@ -0,0 +1,122 @@
+use alloc::string::String;
+use core::fmt;
+
+/// A callback for `LineCapture` that receives every line of the output
+///
+/// The arguments are the line number, starting from 0, and the text of the line without its
+/// newline
+pub type LineCallback<'a> = dyn FnMut(usize, &str) -> fmt::Result + 'a;
+
+/// A writer that hands every completed line of its output to a callback
+///
+/// # Explanation
+///
+/// Tools that post-process reports want the output as whole lines, no matter how it was split
+/// into writes. Put a `LineCapture` behind an `Indented` and the callback is called with each
+/// line as it is finished, after indentation. Output is captured instead of written by default,
+/// or also written to another writer with `tee`. The line after the last newline is only passed
+/// to the callback by `finish`. This is behind the `alloc` feature, since a line is buffered
+/// until it is complete.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, LineCapture};
+///
+/// let mut longest = (0, 0);
+/// let mut measure = |number, line: &str| {
+///     longest = longest.max((line.len(), number));
+///     Ok(())
+/// };
+///
+/// let mut output = String::new();
+/// let mut capture = LineCapture::new(&mut measure).tee(&mut output);
+/// write!(indented(&mut capture).with_str("    "), "short\na longer line\nend")?;
+/// capture.finish()?;
+///
+/// assert_eq!(output, "    short\n    a longer line\n    end");
+/// assert_eq!(longest, (17, 1));
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct LineCapture<'a> {
+    callback: &'a mut LineCallback<'a>,
+    inner: Option<&'a mut dyn fmt::Write>,
+    line: String,
+    number: usize,
+}
+
+impl<'a> LineCapture<'a> {
+    /// Construct a writer that passes its lines to `callback` and writes nothing else
+    pub fn new(callback: &'a mut LineCallback<'a>) -> Self {
+        Self {
+            callback,
+            inner: None,
+            line: String::new(),
+            number: 0,
+        }
+    }
+
+    /// Also write all output to `inner`, as it is written
+    pub fn tee(mut self, inner: &'a mut dyn fmt::Write) -> Self {
+        self.inner = Some(inner);
+        self
+    }
+
+    /// Pass the line after the last newline to the callback, if it is not empty
+    pub fn finish(&mut self) -> fmt::Result {
+        if self.line.is_empty() {
+            return Ok(());
+        }
+
+        self.complete()
+    }
+
+    fn complete(&mut self) -> fmt::Result {
+        let result = (self.callback)(self.number, &self.line);
+        self.line.clear();
+        self.number += 1;
+        result
+    }
+}
+
+impl fmt::Write for LineCapture<'_> {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        if let Some(inner) = &mut self.inner {
+            inner.write_str(s)?;
+        }
+
+        while let Some(end) = s.find('\n') {
+            self.line.push_str(&s[..end]);
+            self.complete()?;
+            s = &s[end + 1..];
+        }
+
+        self.line.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    #[test]
+    fn lines_split_across_writes() {
+        let mut lines = Vec::new();
+        let mut record = |number, line: &str| {
+            lines.push((number, String::from(line)));
+            Ok(())
+        };
+
+        let mut capture = LineCapture::new(&mut record);
+        capture.write_str("a").unwrap();
+        capture.write_str("b\n\nc\n").unwrap();
+        capture.finish().unwrap();
+        drop(capture);
+
+        let expected = [(0, "ab"), (1, ""), (2, "c")];
+        assert!(lines.iter().map(|(n, l)| (*n, l.as_str())).eq(expected));
+    }
+}
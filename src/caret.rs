@@ -0,0 +1,122 @@
+use crate::spaces::write_spaces;
+use crate::width::Column;
+use core::fmt::{self, Write};
+use core::ops::Range;
+
+/// Helper struct for displaying a line of carets under a span of the line above it
+///
+/// # Explanation
+///
+/// Pointing at part of a line of source code, the way compilers report errors, means writing
+/// enough blanks to reach the start of the span and a marker as wide as the span on the next
+/// line. Counting bytes or characters gets this wrong as soon as the line has tabs or wide
+/// characters, so the blanks are measured with `display_width`, and tabs before the span are
+/// written as tabs, which lines them up under the line however wide the terminal shows them. The
+/// span is a byte range of `line`, and an empty span is pointed at with a single caret.
+///
+/// The caret line is written without a gutter of its own. Written through the same `indented`
+/// as the line, a numbered or other gutter that pads its continuation lines lines the carets up
+/// with the line.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, Caret};
+///
+/// let line = "\tlet 名前 = 42;";
+/// let mut output = String::new();
+/// write!(
+///     indented(&mut output).ind(3),
+///     "{}\n{}",
+///     line,
+///     Caret::new(line, 5..11).with_label("unused variable")
+/// )?;
+///
+/// assert_eq!(output, "   3: \tlet 名前 = 42;\n      \t    ^^^^ unused variable");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+///
+/// # Panics
+///
+/// When the span is out of bounds of `line` or not on character boundaries, like slicing `line`.
+#[derive(Debug, Clone)]
+pub struct Caret<'a> {
+    line: &'a str,
+    span: Range<usize>,
+    marker: char,
+    label: Option<&'a str>,
+}
+
+impl<'a> Caret<'a> {
+    /// Construct the carets for `span`, a byte range of `line`
+    pub fn new(line: &'a str, span: Range<usize>) -> Self {
+        Self {
+            line,
+            span,
+            marker: '^',
+            label: None,
+        }
+    }
+
+    /// Sets the character the span is marked with, such as `~` for a secondary span
+    pub fn with_char(mut self, marker: char) -> Self {
+        self.marker = marker;
+        self
+    }
+
+    /// Sets a label written after the carets
+    pub fn with_label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+}
+
+impl fmt::Display for Caret<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut column = Column::default();
+        let mut blanks = 0;
+        for c in self.line[..self.span.start].chars() {
+            if c == '\t' {
+                write_spaces(f, blanks)?;
+                blanks = 0;
+                f.write_char('\t')?;
+                continue;
+            }
+
+            let before = column.width;
+            column.advance(c.encode_utf8(&mut [0; 4]));
+            blanks += column.width - before;
+        }
+        write_spaces(f, blanks)?;
+
+        let mut span = column;
+        span.width = 0;
+        span.advance(&self.line[self.span.clone()]);
+        for _ in 0..span.width.max(1) {
+            f.write_char(self.marker)?;
+        }
+
+        if let Some(label) = self.label {
+            write!(f, " {}", label)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn empty_span_and_escapes() {
+        let line = "\x1b[1mfn\x1b[0m main";
+
+        assert_eq!(Caret::new(line, 10..10).to_string(), "  ^");
+        assert_eq!(
+            Caret::new(line, 11..15).with_char('~').to_string(),
+            "   ~~~~"
+        );
+    }
+}
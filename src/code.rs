@@ -0,0 +1,286 @@
+use crate::{Indent, IndentWriter};
+use core::fmt::{self, Write};
+
+/// A writer for generating source code line by line
+///
+/// # Explanation
+///
+/// Build scripts and procedural macros that emit code as text keep having to track how deeply
+/// nested they currently are. `CodeWriter` keeps that depth for them: `open_block` writes a line
+/// and nests everything after it one level deeper, `close_block` undoes that. Every line is
+/// indented through `Indented`, so a single call can write several lines at once.
+///
+/// ```rust
+/// use indenter::CodeWriter;
+///
+/// let mut w = CodeWriter::new(String::new());
+/// w.open_block("fn main() {")?;
+/// w.line("let x = 1;")?;
+/// w.blank()?;
+/// w.line(format_args!("println!(\"{{}}\", {});", "x"))?;
+/// w.close_block("}")?;
+///
+/// assert_eq!(
+///     w.into_inner(),
+///     "fn main() {\n    let x = 1;\n\n    println!(\"{}\", x);\n}\n"
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct CodeWriter<W> {
+    inner: W,
+    unit: &'static str,
+    language: Language,
+    depth: Indent,
+}
+
+/// The block and comment syntax of a target language for `CodeWriter`
+///
+/// A block is written as `prefix`, the header, then `open` on one line, followed by its body one
+/// level deeper and `close` on a line of its own, which is left out entirely when empty.
+///
+/// ```rust
+/// use indenter::{CodeWriter, Language};
+///
+/// let mut w = CodeWriter::new(String::new()).with_language(Language::PYTHON);
+/// w.comment("generated")?;
+/// w.block("def f()", |w| w.line("return 1"))?;
+///
+/// assert_eq!(w.into_inner(), "# generated\ndef f():\n    return 1\n");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Language {
+    /// Written before the header of a block
+    pub prefix: &'static str,
+    /// Written after the header of a block
+    pub open: &'static str,
+    /// Written on its own line to end a block
+    pub close: &'static str,
+    /// The string repeated once per level of depth
+    pub unit: &'static str,
+    /// Written before every line of a comment
+    pub comment: &'static str,
+}
+
+impl Language {
+    /// Rust and other C-like languages with braced blocks
+    pub const RUST: Language = Language {
+        prefix: "",
+        open: "{",
+        close: "}",
+        unit: "    ",
+        comment: "// ",
+    };
+
+    /// Python and other languages where a colon and indentation delimit blocks
+    pub const PYTHON: Language = Language {
+        prefix: "",
+        open: ":",
+        close: "",
+        unit: "    ",
+        comment: "# ",
+    };
+
+    /// Lisp and other languages made of parenthesized forms
+    pub const LISP: Language = Language {
+        prefix: "(",
+        open: "",
+        close: ")",
+        unit: "  ",
+        comment: ";; ",
+    };
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Language::RUST
+    }
+}
+
+impl<W: fmt::Write> CodeWriter<W> {
+    /// Construct a writer that indents each level with four spaces
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            unit: Language::RUST.unit,
+            language: Language::RUST,
+            depth: Indent::ZERO,
+        }
+    }
+
+    /// Sets the language whose block and comment syntax is written, along with its indentation
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.unit = language.unit;
+        self.language = language;
+        self
+    }
+
+    /// Sets the string repeated once per level of depth
+    pub fn with_str(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// The current nesting depth
+    pub fn depth(&self) -> Indent {
+        self.depth
+    }
+
+    /// Write `line` at the current depth, followed by a newline
+    pub fn line(&mut self, line: impl fmt::Display) -> fmt::Result {
+        let mut writer = IndentWriter::at_depth(&mut self.inner, self.unit, self.depth);
+        writeln!(writer, "{}", line)
+    }
+
+    /// Write each line of `text` as a comment in the current language
+    pub fn comment(&mut self, text: &str) -> fmt::Result {
+        let leader = self.language.comment;
+
+        for line in text.split('\n') {
+            if line.is_empty() {
+                self.line(leader.trim_end())?;
+            } else {
+                self.line(format_args!("{}{}", leader, line))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write an empty line
+    pub fn blank(&mut self) -> fmt::Result {
+        self.inner.write_char('\n')
+    }
+
+    /// Write `line` and nest the lines after it one level deeper
+    pub fn open_block(&mut self, line: impl fmt::Display) -> fmt::Result {
+        self.line(line)?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Leave the innermost block and write `line` at the depth it was opened at
+    pub fn close_block(&mut self, line: impl fmt::Display) -> fmt::Result {
+        self.depth -= 1;
+        self.line(line)
+    }
+
+    /// Write `header` as the start of a block and run `f` one level deeper
+    ///
+    /// The block is delimited according to the current `Language`, braces by default. The end of
+    /// the block is written once `f` returns, including when it returns early with an
+    /// error, and the depth is reset to where the block was opened even if `f` left blocks of its
+    /// own unclosed. The first error encountered is returned.
+    ///
+    /// ```rust
+    /// use indenter::CodeWriter;
+    ///
+    /// let mut w = CodeWriter::new(String::new()).with_str("  ");
+    /// w.block("impl Foo ", |w| {
+    ///     w.block("fn foo(&self) ", |w| w.line("todo!()"))
+    /// })?;
+    ///
+    /// assert_eq!(
+    ///     w.into_inner(),
+    ///     "impl Foo {\n  fn foo(&self) {\n    todo!()\n  }\n}\n"
+    /// );
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn block<F>(&mut self, header: impl fmt::Display, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Self) -> fmt::Result,
+    {
+        let depth = self.depth;
+        let Language {
+            prefix,
+            open,
+            close,
+            ..
+        } = self.language;
+        self.open_block(format_args!("{}{}{}", prefix, header, open))?;
+
+        let result = f(self);
+        self.depth = depth;
+        let close = if close.is_empty() {
+            Ok(())
+        } else {
+            self.line(close)
+        };
+
+        result.and(close)
+    }
+
+    /// Returns a reference to the underlying writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes this writer, returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write + Default> Default for CodeWriter<W> {
+    fn default() -> Self {
+        Self::new(W::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn nested_blocks() {
+        let mut w = CodeWriter::new(String::new()).with_str("\t");
+        w.open_block("mod a {").unwrap();
+        w.open_block("mod b {").unwrap();
+        w.line("fn c() {}\nfn d() {}").unwrap();
+        w.close_block("}").unwrap();
+        w.close_block("}").unwrap();
+
+        assert_eq!(
+            w.get_ref(),
+            "mod a {\n\tmod b {\n\t\tfn c() {}\n\t\tfn d() {}\n\t}\n}\n"
+        );
+        assert_eq!(w.depth(), Indent::ZERO);
+    }
+
+    #[test]
+    fn unbalanced_close_stays_at_margin() {
+        let mut w = CodeWriter::new(String::new());
+        w.close_block("}").unwrap();
+        w.line("x").unwrap();
+
+        assert_eq!(w.into_inner(), "}\nx\n");
+    }
+
+    #[test]
+    fn block_closes_on_error() {
+        let mut w = CodeWriter::new(String::new());
+        let result = w.block("loop ", |w| {
+            w.open_block("if x {")?;
+            w.line("break;")?;
+            Err(fmt::Error)
+        });
+
+        assert!(result.is_err());
+        assert_eq!(w.depth(), Indent::ZERO);
+        assert_eq!(w.into_inner(), "loop {\n    if x {\n        break;\n}\n");
+    }
+
+    #[test]
+    fn lisp_forms() {
+        let mut w = CodeWriter::<String>::default().with_language(Language::LISP);
+        w.comment("a\n\nb").unwrap();
+        w.block("defun f (x)", |w| w.line("(* x 2)")).unwrap();
+
+        assert_eq!(
+            w.into_inner(),
+            ";; a\n;;\n;; b\n(defun f (x)\n  (* x 2)\n)\n"
+        );
+    }
+}
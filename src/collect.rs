@@ -0,0 +1,188 @@
+use crate::{indented, Section, DEFAULT_INDENTATION};
+use core::fmt::{self, Write};
+use std::sync::Mutex;
+
+/// A collector for indented sections rendered on several threads, merged in a fixed order
+///
+/// # Explanation
+///
+/// Output rendered in parallel arrives in whatever order the threads finish in. Each thread
+/// instead writes its output to a `SectionBuffer` taken from a shared collector, under a key that
+/// decides its place in the merged output. `merge` then writes every section in key order, with
+/// its header on a line of its own and its body indented underneath it, so every section gets the
+/// same indentation no matter which thread rendered it. Sections with the same key are merged in
+/// the order they were finished in.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::SectionCollector;
+///
+/// let collector = SectionCollector::new().with_str("  ");
+/// std::thread::scope(|scope| {
+///     for test in 0..3 {
+///         let collector = &collector;
+///         scope.spawn(move || {
+///             let mut section = collector.section(test).with_header(format!("test {}:", test));
+///             write!(section, "step 1\nstep 2").unwrap();
+///         });
+///     }
+/// });
+///
+/// let mut output = String::new();
+/// collector.merge(&mut output)?;
+///
+/// assert_eq!(
+///     output,
+///     "\
+/// test 0:
+///   step 1
+///   step 2
+/// test 1:
+///   step 1
+///   step 2
+/// test 2:
+///   step 1
+///   step 2
+/// "
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SectionCollector {
+    sections: Mutex<Vec<Collected>>,
+    indentation: &'static str,
+    separator: &'static str,
+}
+
+#[derive(Debug)]
+struct Collected {
+    key: usize,
+    header: Option<String>,
+    body: String,
+}
+
+/// A buffer for one section of a `SectionCollector`, added to the collector when dropped
+#[derive(Debug)]
+pub struct SectionBuffer<'a> {
+    collector: &'a SectionCollector,
+    section: Option<Collected>,
+}
+
+impl SectionCollector {
+    /// Construct a collector that indents section bodies by `DEFAULT_INDENTATION` and ends every
+    /// section with a newline
+    pub fn new() -> Self {
+        Self {
+            sections: Mutex::new(Vec::new()),
+            indentation: DEFAULT_INDENTATION,
+            separator: "\n",
+        }
+    }
+
+    /// Sets the string used to indent the body of every section
+    pub fn with_str(mut self, indentation: &'static str) -> Self {
+        self.indentation = indentation;
+        self
+    }
+
+    /// Sets the string written after every section
+    pub fn with_separator(mut self, separator: &'static str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Start a section that is merged in the position given by `key`
+    pub fn section(&self, key: usize) -> SectionBuffer<'_> {
+        SectionBuffer {
+            collector: self,
+            section: Some(Collected {
+                key,
+                header: None,
+                body: String::new(),
+            }),
+        }
+    }
+
+    /// Write every finished section in key order
+    pub fn merge(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        let mut sections = self.sections.lock().map_err(|_| fmt::Error)?;
+        sections.sort_by_key(|section| section.key);
+
+        for section in sections.iter() {
+            let body = section.body.trim_end_matches('\n');
+
+            match &section.header {
+                Some(header) => write!(
+                    f,
+                    "{}",
+                    Section::new(header, body).with_str(self.indentation)
+                )?,
+                None => write!(indented(f).with_str(self.indentation), "{}", body)?,
+            }
+
+            f.write_str(self.separator)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SectionCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SectionBuffer<'_> {
+    /// Sets the header written above the body of the section
+    pub fn with_header(mut self, header: impl fmt::Display) -> Self {
+        if let Some(section) = &mut self.section {
+            section.header = Some(header.to_string());
+        }
+        self
+    }
+
+    /// Add the section to its collector
+    ///
+    /// This is the same as dropping the buffer.
+    pub fn finish(self) {}
+}
+
+impl fmt::Write for SectionBuffer<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match &mut self.section {
+            Some(section) => section.body.write_str(s),
+            None => Err(fmt::Error),
+        }
+    }
+}
+
+impl Drop for SectionBuffer<'_> {
+    fn drop(&mut self) {
+        if let (Some(section), Ok(mut sections)) =
+            (self.section.take(), self.collector.sections.lock())
+        {
+            sections.push(section);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_decide_the_order() {
+        let collector = SectionCollector::new().with_separator("\n\n");
+        writeln!(collector.section(2), "second").unwrap();
+        collector.section(1).with_header("first:").finish();
+
+        let mut output = String::new();
+        collector.merge(&mut output).unwrap();
+
+        assert_eq!(
+            output,
+            format!("first:\n\n\n{}second\n\n", crate::DEFAULT_INDENTATION)
+        );
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(feature = "std")]
+use std::ffi::OsStr;
+
+/// Whether output is styled with colors
+///
+/// # Explanation
+///
+/// `Auto` follows the conventions end users set in their environment: a `CLICOLOR_FORCE` that is
+/// set and not `0` turns colors on, and otherwise a non-empty `NO_COLOR` or a `CLICOLOR` of `0`
+/// turns them off. `Always` and `Never` override the environment, for example from a `--color`
+/// flag. The decision from `enabled` is meant for `Indented::color`, and can be reused to style
+/// other content the same way. Terminals are not detected, since the output of an indenter is not
+/// necessarily written to one.
+///
+/// ```rust
+/// # #[cfg(feature = "std")]
+/// # fn main() -> core::fmt::Result {
+/// use core::fmt::Write;
+/// use indenter::{indented, ColorChoice};
+///
+/// std::env::set_var("NO_COLOR", "1");
+/// let color = ColorChoice::Auto.enabled();
+/// assert!(!color);
+///
+/// let mut dimmed = |gutter: &str, f: &mut dyn Write| write!(f, "\x1b[2m{}\x1b[0m", gutter);
+///
+/// let mut output = String::new();
+/// let mut indented = indented(&mut output)
+///     .with_str("| ")
+///     .stylize_gutter(&mut dimmed)
+///     .color(color);
+/// write!(indented, "a")?;
+///
+/// assert_eq!(output, "| a");
+/// assert!(ColorChoice::Always.enabled());
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// Decide from the environment, the default
+    #[default]
+    Auto,
+    /// Always use colors
+    Always,
+    /// Never use colors
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether colors should be used, reading the environment for `Auto`
+    ///
+    /// This is behind the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => from_vars(
+                std::env::var_os("CLICOLOR_FORCE").as_deref(),
+                std::env::var_os("NO_COLOR").as_deref(),
+                std::env::var_os("CLICOLOR").as_deref(),
+            ),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Decides whether to use colors from `CLICOLOR_FORCE`, `NO_COLOR` and `CLICOLOR`
+#[cfg(feature = "std")]
+pub(crate) fn from_vars(
+    force: Option<&OsStr>,
+    no_color: Option<&OsStr>,
+    clicolor: Option<&OsStr>,
+) -> bool {
+    if force.is_some_and(|force| force != "0") {
+        return true;
+    }
+
+    let no_color = matches!(no_color, Some(no_color) if !no_color.is_empty());
+    !no_color && clicolor != Some(OsStr::new("0"))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    #[test]
+    fn force_overrides_no_color() {
+        use super::from_vars;
+        use std::ffi::OsStr;
+
+        let set = |value| Some(OsStr::new(value));
+        assert!(from_vars(None, None, None));
+        assert!(from_vars(None, set(""), set("1")));
+        assert!(!from_vars(None, set("1"), None));
+        assert!(!from_vars(set("0"), None, set("0")));
+        assert!(from_vars(set("1"), set("1"), set("0")));
+    }
+}
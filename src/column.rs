@@ -0,0 +1,99 @@
+use crate::spaces::write_spaces;
+use crate::width::Column;
+use core::fmt;
+
+/// A writer that keeps track of the column its output has reached on the current line
+///
+/// # Explanation
+///
+/// Aligning an annotation after some text, or deciding whether a word still fits on a line,
+/// needs to know where the last line ends. This adapter measures everything written through it
+/// with `display_width`, so wide characters count twice and ANSI escape sequences not at all, and
+/// resets the column after every newline. Wrapping it in `indented` includes the indentation of
+/// each line in the count. `pad_to` writes spaces up to a column, to line text up in columns.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, track_columns};
+///
+/// let mut output = String::new();
+/// let mut tracked = track_columns(&mut output);
+/// for (code, comment) in [("let x = 1;", "one"), ("x += 41;", "the answer")] {
+///     write!(indented(&mut tracked).with_str("    "), "{}", code)?;
+///     tracked.pad_to(20)?;
+///     writeln!(tracked, "// {}", comment)?;
+/// }
+///
+/// assert_eq!(
+///     output,
+///     "    let x = 1;      // one\n    x += 41;        // the answer\n"
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct ColumnTracker<'a, W: ?Sized> {
+    inner: &'a mut W,
+    column: Column,
+}
+
+/// Helper function for creating a writer that keeps track of the column of its output
+pub fn track_columns<W: ?Sized>(inner: &mut W) -> ColumnTracker<'_, W> {
+    ColumnTracker {
+        inner,
+        column: Column::default(),
+    }
+}
+
+impl<W: ?Sized> ColumnTracker<'_, W> {
+    /// Returns the display width of the output on the current line
+    pub fn column(&self) -> usize {
+        self.column.width
+    }
+}
+
+impl<W: fmt::Write + ?Sized> ColumnTracker<'_, W> {
+    /// Write spaces up to `column`, or nothing if the current line already reaches it
+    pub fn pad_to(&mut self, column: usize) -> fmt::Result {
+        let padding = column.saturating_sub(self.column.width);
+        write_spaces(self.inner, padding)?;
+        self.column.width += padding;
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for ColumnTracker<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_str(s)?;
+
+        match s.rfind('\n') {
+            Some(newline) => {
+                self.column = Column::default();
+                self.column.advance(&s[newline + 1..]);
+            }
+            None => self.column.advance(s),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::String;
+    use fmt::Write;
+
+    #[test]
+    fn wide_characters_and_long_lines() {
+        let mut output = String::new();
+        let mut tracked = track_columns(&mut output);
+        tracked.write_str("ab\n日本").unwrap();
+        assert_eq!(tracked.column(), 4);
+
+        tracked.pad_to(3).unwrap();
+        tracked.pad_to(6).unwrap();
+        assert_eq!(tracked.column(), 6);
+        assert_eq!(output, "ab\n日本  ");
+    }
+}
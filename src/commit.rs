@@ -0,0 +1,226 @@
+use crate::wrap::marker_len;
+use crate::{indented, reflow, Format, Indent, Indentation, LIST_MARKERS};
+use core::fmt::{self, Write};
+use core::ops::Range;
+
+/// Helper struct for laying out a git commit message or changelog entry
+///
+/// # Explanation
+///
+/// Tools that generate commit messages tend to get the conventions of `git log` slightly wrong.
+/// This preset writes the subject line as it is, separates the body from it with a blank line,
+/// and wraps the body at 72 columns. Lines of a paragraph are joined before they are wrapped
+/// again, and list items starting with `-`, `*`, `+` or a number such as `1.` are wrapped with a
+/// hanging indent under the start of their text. Lines indented outside of a list item, such as
+/// code, are kept as they are, as is a last paragraph made only of trailers like
+/// `Signed-off-by: ...`. Runs of blank lines are collapsed into one, and the message always ends
+/// in a single newline.
+///
+/// ```rust
+/// use indenter::commit_message;
+///
+/// let message = "Add a commit preset
+/// This collects the conventions in one place
+/// so every tool agrees on them.
+///
+/// - wraps the body at 32 columns in this example, which is narrow
+///
+/// Signed-off-by: Jane <jane@example.com>";
+///
+/// assert_eq!(
+///     commit_message(message).with_width(32).to_string(),
+///     "Add a commit preset
+///
+/// This collects the conventions in
+/// one place so every tool agrees
+/// on them.
+///
+/// - wraps the body at 32 columns
+///   in this example, which is
+///   narrow
+///
+/// Signed-off-by: Jane <jane@example.com>
+/// "
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CommitMessage<'a> {
+    text: &'a str,
+    width: usize,
+}
+
+/// Create a `CommitMessage` that lays out `text`, whose first line is the subject
+pub fn commit_message(text: &str) -> CommitMessage<'_> {
+    CommitMessage { text, width: 72 }
+}
+
+impl CommitMessage<'_> {
+    /// Sets the width the body is wrapped at, 72 columns by default
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Writes a block of the body, whose lines are `range` of `body`
+    fn write_block(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        body: &str,
+        block: Block,
+        range: Range<usize>,
+    ) -> fmt::Result {
+        let text = &body[range.clone()];
+        match block {
+            Block::Paragraph => write!(f, "{}", reflow(text, self.width)),
+            Block::Verbatim | Block::Trailers => f.write_str(text.trim_end()),
+            Block::Item { text_start } => {
+                let indent = text_start - range.start;
+                f.write_str(&body[range.start..text_start])?;
+
+                let indentation = Indentation::new(Indent(indent), " ");
+                let mut inserter = move |_, f: &mut dyn Write| write!(f, "{}", indentation);
+                let width = self.width.saturating_sub(indent).max(1);
+
+                write!(
+                    indented(f)
+                        .with_format(Format::Custom {
+                            inserter: &mut inserter,
+                        })
+                        .skip_lines(1),
+                    "{}",
+                    reflow(&body[text_start..range.end], width)
+                )
+            }
+        }
+    }
+}
+
+/// The kinds of blocks the body of a message is made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    /// Prose, joined and wrapped again
+    Paragraph,
+    /// A list item, whose text starts at `text_start` after the marker
+    Item { text_start: usize },
+    /// An indented line outside of a list item, such as code, written as it is
+    Verbatim,
+    /// The trailers ending the message, written as they are
+    Trailers,
+}
+
+/// Whether `line` looks like a trailer, such as `Signed-off-by: Jane <jane@example.com>`
+fn is_trailer(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(token, _)| {
+        !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Returns where the last paragraph of `body` starts, if every line of it is a trailer
+///
+/// This follows git, which only takes trailers from the end of a message.
+fn trailers_start(body: &str) -> Option<usize> {
+    let mut start = 0;
+    let mut offset = 0;
+    for line in body.split('\n') {
+        offset += line.len() + 1;
+        if line.trim().is_empty() {
+            start = offset;
+        }
+    }
+
+    let paragraph = body.get(start..).unwrap_or_default();
+    (!paragraph.is_empty() && paragraph.split('\n').all(is_trailer)).then_some(start)
+}
+
+impl fmt::Display for CommitMessage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.text.trim_end();
+        let (subject, body) = text.split_once('\n').unwrap_or((text, ""));
+        f.write_str(subject)?;
+
+        let trailers = trailers_start(body);
+        let mut open: Option<(Block, Range<usize>)> = None;
+        let mut separator = "\n\n";
+        let mut offset = 0;
+        for line in body.split('\n') {
+            let range = offset..offset + line.len();
+            offset = range.end + 1;
+
+            if line.trim().is_empty() {
+                if let Some((block, range)) = open.take() {
+                    self.write_block(f, body, block, range)?;
+                    separator = "\n\n";
+                }
+                continue;
+            }
+
+            let leading = line.len() - line.trim_start().len();
+            let marker = marker_len(&line[leading..], LIST_MARKERS);
+            let continues = match &open {
+                _ if marker.is_some() => false,
+                Some((Block::Paragraph, _)) => leading == 0,
+                Some((Block::Item { .. }, _)) => leading > 0,
+                Some((Block::Trailers, _)) => true,
+                Some((Block::Verbatim, _)) | None => false,
+            };
+
+            if let (true, Some((_, open))) = (continues, &mut open) {
+                open.end = range.end;
+                continue;
+            }
+
+            if let Some((block, range)) = open.take() {
+                self.write_block(f, body, block, range)?;
+            }
+            f.write_str(separator)?;
+            separator = "\n";
+
+            let block = match marker {
+                Some(len) => Block::Item {
+                    text_start: range.start + leading + len,
+                },
+                None if leading > 0 => Block::Verbatim,
+                None if trailers == Some(range.start) => Block::Trailers,
+                None => Block::Paragraph,
+            };
+            open = Some((block, range));
+        }
+
+        if let Some((block, range)) = open {
+            self.write_block(f, body, block, range)?;
+        }
+
+        f.write_char('\n')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn nested_items_and_code() {
+        let message =
+            "Long subject line\n\n\n1. first item\n   goes on\n   - nested\n\n    x = 1;\n";
+
+        assert_eq!(
+            commit_message(message).with_width(12).to_string(),
+            "Long subject line\n\n1. first\n   item goes\n   on\n   - nested\n\n    x = 1;\n"
+        );
+    }
+
+    #[test]
+    fn trailers_only_at_the_end() {
+        let message =
+            "Subj\n\nNote: this is a very long body sentence\n\nAcked-by: A\nsee below\n\n\
+                       Signed-off-by: B\nFixes: #1\n";
+
+        assert_eq!(
+            commit_message(message).with_width(30).to_string(),
+            "Subj\n\nNote: this is a very long body\nsentence\n\nAcked-by: A see below\n\n\
+             Signed-off-by: B\nFixes: #1\n"
+        );
+    }
+}
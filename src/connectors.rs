@@ -0,0 +1,272 @@
+use crate::spaces::write_spaces;
+use crate::width::Column;
+use core::cmp::Reverse;
+use core::fmt::{self, Write};
+use core::iter;
+use core::ops::Range;
+
+/// A label for a span of a line, drawn by `Connectors`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Label<'a> {
+    span: Range<usize>,
+    text: &'a str,
+}
+
+impl<'a> Label<'a> {
+    /// Construct a label for `span`, a byte range of the line
+    pub fn new(span: Range<usize>, text: &'a str) -> Self {
+        Self { span, text }
+    }
+
+    /// Returns the byte offset of the character in the middle of the span, which the arm of the
+    /// label is drawn from
+    fn anchor(&self, line: &str) -> usize {
+        let width = columns(&line[self.span.clone()])
+            .map(|(_, _, width)| width)
+            .sum::<usize>();
+        let middle = width.saturating_sub(1) / 2;
+
+        let mut column = 0;
+        for (offset, _, width) in columns(&line[self.span.clone()]) {
+            if width > 0 && column + width > middle {
+                return self.span.start + offset;
+            }
+            column += width;
+        }
+
+        self.span.start
+    }
+}
+
+/// Returns the characters of `line` with their byte offsets and display widths
+fn columns(line: &str) -> impl Iterator<Item = (usize, char, usize)> + '_ {
+    let mut column = Column::default();
+    line.char_indices().map(move |(offset, c)| {
+        let before = column.width;
+        column.advance(c.encode_utf8(&mut [0; 4]));
+        (offset, c, column.width - before)
+    })
+}
+
+/// Helper struct for drawing the connectors from spans of a line to their labels
+///
+/// # Explanation
+///
+/// Graphical diagnostics, in the style of `miette`, underline every labelled span of a line and
+/// connect each span to its label with an arm drawn on the lines below. The rightmost label is
+/// written first, and vertical connectors run down from every span further left until its own arm
+/// is reached, so arms never cross. Labels anchored at the same character are stacked under it in
+/// the order they were given. Widths are measured with `display_width` and tabs of the line
+/// are kept as tabs, as for `Caret`.
+///
+/// Like `Caret`, the connectors have no gutter of their own, and are written through the same
+/// `indented` as the line to get one that lines them up with it.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, Connectors, Label};
+///
+/// let line = "let total = price * count;";
+/// let labels = [
+///     Label::new(12..17, "this is a `f64`"),
+///     Label::new(20..25, "this is a `u32`"),
+/// ];
+///
+/// let mut output = String::new();
+/// write!(
+///     indented(&mut output).ind(4),
+///     "{}\n{}",
+///     line,
+///     Connectors::new(line, &labels)
+/// )?;
+///
+/// assert_eq!(
+///     output.lines().collect::<Vec<_>>(),
+///     [
+///         "   4: let total = price * count;",
+///         "                  ──┬──   ──┬──",
+///         "                    │       ╰── this is a `u32`",
+///         "                    ╰── this is a `f64`",
+///     ]
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+///
+/// # Panics
+///
+/// When a span is out of bounds of `line` or not on character boundaries, like slicing `line`.
+#[derive(Debug, Clone, Copy)]
+pub struct Connectors<'a> {
+    line: &'a str,
+    labels: &'a [Label<'a>],
+}
+
+impl<'a> Connectors<'a> {
+    /// Construct the connectors for `labels` of spans of `line`
+    pub fn new(line: &'a str, labels: &'a [Label<'a>]) -> Self {
+        Self { line, labels }
+    }
+
+    /// Returns the anchors of the labels
+    fn anchors(&self) -> impl Iterator<Item = usize> + '_ {
+        let line = self.line;
+        self.labels.iter().map(move |label| label.anchor(line))
+    }
+
+    /// Returns the characters of the line, followed by a blank one past its end for empty spans
+    /// there to point at
+    fn columns(&self) -> impl Iterator<Item = (usize, char, usize)> + '_ {
+        columns(self.line).chain(iter::once((self.line.len(), ' ', 1)))
+    }
+
+    /// Writes the line that underlines every span
+    fn underline(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let last = self
+            .labels
+            .iter()
+            .map(|label| {
+                label
+                    .anchor(self.line)
+                    .max(label.span.end.saturating_sub(1))
+            })
+            .max();
+        let mut blanks = 0;
+
+        for (offset, c, width) in self.columns() {
+            if last.is_some_and(|last| offset > last) {
+                break;
+            }
+
+            let spanned = self.labels.iter().any(|label| label.span.contains(&offset));
+            if self.anchors().any(|anchor| anchor == offset) {
+                write_spaces(f, blanks)?;
+                blanks = 0;
+                f.write_char('┬')?;
+                for _ in 1..width {
+                    f.write_char('─')?;
+                }
+            } else if spanned {
+                write_spaces(f, blanks)?;
+                blanks = 0;
+                for _ in 0..width {
+                    f.write_char('─')?;
+                }
+            } else if c == '\t' {
+                write_spaces(f, blanks)?;
+                blanks = 0;
+                f.write_char('\t')?;
+            } else {
+                blanks += width;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the anchor of every label with the order its arm is drawn in, from the rightmost
+    /// anchor to the leftmost and in the order the labels were given for the same anchor
+    fn keyed(&self) -> impl Iterator<Item = ((Reverse<usize>, usize), &Label<'a>)> + '_ {
+        let line = self.line;
+        self.labels
+            .iter()
+            .enumerate()
+            .map(move |(index, label)| ((Reverse(label.anchor(line)), index), label))
+    }
+
+    /// Writes the arm of the label with the drawing order `key`, and the connectors of the labels
+    /// drawn after it
+    fn arm(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        key: (Reverse<usize>, usize),
+        text: &str,
+    ) -> fmt::Result {
+        let anchor = (key.0).0;
+        let below = |offset| {
+            self.keyed()
+                .any(|(other, _)| other > key && (other.0).0 == offset)
+        };
+        let mut blanks = 0;
+
+        for (offset, c, width) in self.columns() {
+            if offset == anchor {
+                break;
+            }
+
+            if below(offset) {
+                write_spaces(f, blanks)?;
+                f.write_char('│')?;
+                blanks = width.saturating_sub(1);
+            } else if c == '\t' {
+                write_spaces(f, blanks)?;
+                blanks = 0;
+                f.write_char('\t')?;
+            } else {
+                blanks += width;
+            }
+        }
+
+        write_spaces(f, blanks)?;
+        let corner = if below(anchor) { '├' } else { '╰' };
+        write!(f, "{}── {}", corner, text)
+    }
+}
+
+impl fmt::Display for Connectors<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.underline(f)?;
+
+        let mut previous = None;
+        loop {
+            let next = self
+                .keyed()
+                .filter(|&(key, _)| match previous {
+                    Some(previous) => key > previous,
+                    None => true,
+                })
+                .min_by_key(|&(key, _)| key);
+
+            match next {
+                Some((key, label)) => {
+                    f.write_char('\n')?;
+                    self.arm(f, key, label.text)?;
+                    previous = Some(key);
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn single_character_spans_and_tabs() {
+        let line = "\ta(b)";
+        let labels = [Label::new(1..2, "call"), Label::new(3..4, "argument")];
+
+        assert_eq!(
+            Connectors::new(line, &labels).to_string(),
+            "\t┬ ┬\n\t│ ╰── argument\n\t╰── call"
+        );
+    }
+
+    #[test]
+    fn labels_sharing_an_anchor() {
+        let line = "foo(bar)";
+        let labels = [
+            Label::new(0..3, "first"),
+            Label::new(4..7, "argument"),
+            Label::new(0..3, "second"),
+        ];
+
+        assert_eq!(
+            Connectors::new(line, &labels).to_string(),
+            "─┬─ ─┬─\n │   ╰── argument\n ├── first\n ╰── second"
+        );
+    }
+}
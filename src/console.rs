@@ -0,0 +1,242 @@
+use alloc::string::String;
+use core::fmt;
+use core::mem;
+
+/// A destination for whole lines of output with nested groups, such as the browser console
+///
+/// # Explanation
+///
+/// Consoles take output one complete message at a time, and show nesting with collapsible
+/// groups rather than leading whitespace. The browser console is provided as `WebConsole` behind
+/// the `wasm` feature, and this trait can be implemented on top of the console bindings of other
+/// platforms. Either is written to through a `ConsoleWriter`.
+pub trait ConsoleSink {
+    /// Write one line of output
+    fn log(&mut self, line: &str);
+
+    /// Write one line of error output, the same as `log` unless overridden
+    fn error(&mut self, line: &str) {
+        self.log(line);
+    }
+
+    /// Start a nested group with the given label
+    fn group(&mut self, label: &str);
+
+    /// End the innermost group
+    fn group_end(&mut self);
+}
+
+impl<S: ConsoleSink + ?Sized> ConsoleSink for &mut S {
+    fn log(&mut self, line: &str) {
+        (**self).log(line);
+    }
+
+    fn error(&mut self, line: &str) {
+        (**self).error(line);
+    }
+
+    fn group(&mut self, label: &str) {
+        (**self).group(label);
+    }
+
+    fn group_end(&mut self) {
+        (**self).group_end();
+    }
+}
+
+/// The browser console, through `console.log`, `console.error` and `console.group`
+///
+/// This is behind the `wasm` feature, and only does anything when running in a browser or another
+/// JavaScript host with a console.
+///
+/// ```rust,no_run
+/// use core::fmt::Write;
+/// use indenter::{ConsoleWriter, WebConsole};
+///
+/// let mut console = ConsoleWriter::new(WebConsole);
+/// writeln!(console, "error: build failed")?;
+/// console.group("Caused by:", |console| writeln!(console, "missing file"))?;
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[cfg(feature = "wasm")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WebConsole;
+
+#[cfg(feature = "wasm")]
+impl ConsoleSink for WebConsole {
+    fn log(&mut self, line: &str) {
+        web_sys::console::log_1(&line.into());
+    }
+
+    fn error(&mut self, line: &str) {
+        web_sys::console::error_1(&line.into());
+    }
+
+    fn group(&mut self, label: &str) {
+        web_sys::console::group_1(&label.into());
+    }
+
+    fn group_end(&mut self) {
+        web_sys::console::group_end();
+    }
+}
+
+/// A writer that batches its output into lines for a `ConsoleSink`
+///
+/// # Explanation
+///
+/// Output is buffered until a newline completes a line, which is then handed to the sink as a
+/// single message. Nesting is written as console groups through `group`, instead of as
+/// indentation, so the console can collapse it. This is behind the `alloc` feature, since a line
+/// has to be buffered until it is complete.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{ConsoleSink, ConsoleWriter};
+///
+/// #[derive(Default)]
+/// struct Transcript(Vec<String>);
+///
+/// impl ConsoleSink for Transcript {
+///     fn log(&mut self, line: &str) {
+///         self.0.push(format!("log {}", line));
+///     }
+///
+///     fn group(&mut self, label: &str) {
+///         self.0.push(format!("group {}", label));
+///     }
+///
+///     fn group_end(&mut self) {
+///         self.0.push("end".into());
+///     }
+/// }
+///
+/// let mut console = ConsoleWriter::new(Transcript::default());
+/// writeln!(console, "error: build failed")?;
+/// console.group("Caused by:", |console| write!(console, "missing file"))?;
+///
+/// assert_eq!(
+///     console.into_inner().0,
+///     ["log error: build failed", "group Caused by:", "log missing file", "end"]
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ConsoleWriter<S: ConsoleSink> {
+    sink: S,
+    line: String,
+    error: bool,
+}
+
+impl<S: ConsoleSink> ConsoleWriter<S> {
+    /// Construct a writer that sends its lines to `sink`
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            line: String::new(),
+            error: false,
+        }
+    }
+
+    /// Sets whether lines are sent to the sink as errors
+    ///
+    /// This applies to every line completed from now on, including a partial line that has
+    /// already been written.
+    pub fn set_error(&mut self, error: bool) {
+        self.error = error;
+    }
+
+    /// Write the output of `f` in a nested group labelled `label`
+    ///
+    /// Any partial line is completed before the group starts and when it ends, and the group is
+    /// ended even if `f` fails.
+    pub fn group<F>(&mut self, label: &str, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Self) -> fmt::Result,
+    {
+        self.flush();
+        self.sink.group(label);
+        let result = f(self);
+        self.flush();
+        self.sink.group_end();
+        result
+    }
+
+    /// Send a partial line to the sink, if there is one
+    pub fn flush(&mut self) {
+        if !self.line.is_empty() {
+            let line = mem::take(&mut self.line);
+            self.emit(&line);
+        }
+    }
+
+    /// Flush any partial line and return the sink
+    pub fn into_inner(mut self) -> S {
+        self.flush();
+        self.sink
+    }
+
+    fn emit(&mut self, line: &str) {
+        if self.error {
+            self.sink.error(line);
+        } else {
+            self.sink.log(line);
+        }
+    }
+}
+
+impl<S: ConsoleSink> fmt::Write for ConsoleWriter<S> {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        while let Some(end) = s.find('\n') {
+            if self.line.is_empty() {
+                self.emit(&s[..end]);
+            } else {
+                self.line.push_str(&s[..end]);
+                let line = mem::take(&mut self.line);
+                self.emit(&line);
+            }
+
+            s = &s[end + 1..];
+        }
+
+        self.line.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    #[derive(Default)]
+    struct Levels(Vec<(bool, String)>);
+
+    impl ConsoleSink for Levels {
+        fn log(&mut self, line: &str) {
+            self.0.push((false, line.into()));
+        }
+
+        fn error(&mut self, line: &str) {
+            self.0.push((true, line.into()));
+        }
+
+        fn group(&mut self, _: &str) {}
+
+        fn group_end(&mut self) {}
+    }
+
+    #[test]
+    fn lines_are_batched() {
+        let mut levels = Levels::default();
+        let mut console = ConsoleWriter::new(&mut levels);
+        console.write_str("a").unwrap();
+        console.write_str("b\n\nc").unwrap();
+        console.set_error(true);
+        console.into_inner();
+
+        let expected = [(false, "ab"), (false, ""), (true, "c")];
+        assert!(levels.0.iter().map(|(e, l)| (*e, l.as_str())).eq(expected));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{indented, Format};
+use core::fmt::{self, Write};
+
+/// A preset for marking the continuation lines of multi-line log messages
+///
+/// # Explanation
+///
+/// Log collectors that read files line by line treat every line as a record of its own, so a
+/// multi-line message such as a backtrace gets split apart. This preset writes the first line of
+/// a message as it is, and starts every later line, blank ones included, with a marker, `"| "` by
+/// default, so a parser can join a line that starts with the marker onto the record before it.
+/// With `aligned` the marker is padded with spaces so the text of continuation lines lines up
+/// with the text after the header of the record.
+///
+/// `message` displays a message with the preset, and `write_io` writes one to an `io::Write`
+/// behind the `std` feature, for messages that aren't necessarily UTF-8.
+///
+/// ```rust
+/// use indenter::LogContinuation;
+///
+/// let header = "2024-01-01 ERROR ";
+/// let preset = LogContinuation::new().aligned(header.len());
+/// let line = format!("{}{}", header, preset.message("request failed\n\ncaused by: timeout"));
+///
+/// assert_eq!(
+///     line,
+///     "2024-01-01 ERROR request failed\n|                \n|                caused by: timeout"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LogContinuation<'a> {
+    marker: &'a str,
+    width: usize,
+}
+
+impl Default for LogContinuation<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> LogContinuation<'a> {
+    /// Construct a preset that starts continuation lines with `"| "`
+    pub fn new() -> Self {
+        Self {
+            marker: "| ",
+            width: 0,
+        }
+    }
+
+    /// Sets the marker continuation lines start with
+    pub fn with_marker(mut self, marker: &'a str) -> Self {
+        self.marker = marker;
+        self
+    }
+
+    /// Pad the marker with spaces up to `width` columns, usually the width of the record's header
+    ///
+    /// A marker that is already as wide is written without padding.
+    pub fn aligned(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Display `message` with its continuation lines marked
+    pub fn message<T: fmt::Display>(self, message: T) -> Continued<'a, T> {
+        Continued {
+            message,
+            preset: self,
+        }
+    }
+
+    /// Write the marker and its padding
+    fn write_prefix(&self, f: &mut dyn Write) -> fmt::Result {
+        f.write_str(self.marker)?;
+        let pad = self.width.saturating_sub(self.marker.chars().count());
+        write!(f, "{:pad$}", "", pad = pad)
+    }
+}
+
+#[cfg(feature = "std")]
+impl LogContinuation<'_> {
+    /// Write `message` to `writer` with its continuation lines marked
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    /// use indenter::LogContinuation;
+    ///
+    /// let mut log = Vec::new();
+    /// log.write_all(b"WARN ")?;
+    /// LogContinuation::new().write_io(&mut log, b"disk almost full\n/var: 97%")?;
+    ///
+    /// assert_eq!(log, b"WARN disk almost full\n| /var: 97%");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn write_io<W: std::io::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        message: &[u8],
+    ) -> std::io::Result<()> {
+        use std::io::Write as _;
+
+        let mut inserter = |_, f: &mut dyn Write| self.write_prefix(f);
+        let mut indented = crate::indented_io(writer)
+            .with_format(Format::Custom {
+                inserter: &mut inserter,
+            })
+            .skip_lines(1)
+            .skip_empty(false);
+
+        indented.write_all(message)?;
+        indented.flush()
+    }
+}
+
+/// Helper struct for displaying a message with the continuation lines marked by a `LogContinuation`
+#[derive(Debug, Clone, Copy)]
+pub struct Continued<'a, T> {
+    message: T,
+    preset: LogContinuation<'a>,
+}
+
+impl<T: fmt::Display> fmt::Display for Continued<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let preset = self.preset;
+        let mut inserter = move |_, f: &mut dyn Write| preset.write_prefix(f);
+
+        write!(
+            indented(f)
+                .with_format(Format::Custom {
+                    inserter: &mut inserter,
+                })
+                .skip_lines(1)
+                .skip_empty(false),
+            "{}",
+            self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn wide_markers_are_not_padded() {
+        let preset = LogContinuation::new().with_marker(">>> ").aligned(2);
+
+        assert_eq!(preset.message("a\nb").to_string(), "a\n>>> b");
+        assert_eq!(preset.message("single").to_string(), "single");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_and_fmt_agree() {
+        let preset = LogContinuation::new().aligned(4);
+        let message = "first\n\nsecond\n";
+
+        let mut output = Vec::new();
+        preset.write_io(&mut output, message.as_bytes()).unwrap();
+
+        assert_eq!(output, preset.message(message).to_string().as_bytes());
+    }
+}
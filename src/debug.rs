@@ -0,0 +1,290 @@
+use crate::indented;
+use core::fmt::{self, Write};
+
+/// A `debug_struct` builder that nests fields through `Indented`
+///
+/// # Explanation
+///
+/// The builders on `fmt::Formatter` always indent pretty printed output by four spaces. This
+/// builder produces the same layout, but writes each field through an `Indented` using a
+/// configurable unit, so hand written `Debug` impls can pick their own indentation. Without the
+/// alternate flag the output is identical to the standard builder.
+///
+/// ```rust
+/// use core::fmt;
+///
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// impl fmt::Debug for Point {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         indenter::debug_struct(f, "Point")
+///             .with_str("  ")
+///             .field("x", &self.x)
+///             .field("y", &self.y)
+///             .finish()
+///     }
+/// }
+///
+/// let point = Point { x: 1, y: 2 };
+///
+/// assert_eq!(format!("{:?}", point), "Point { x: 1, y: 2 }");
+/// assert_eq!(format!("{:#?}", point), "Point {\n  x: 1,\n  y: 2,\n}");
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct DebugStruct<'a, 'b> {
+    builder: Builder<'a, 'b>,
+}
+
+/// A `debug_tuple` builder that nests fields through `Indented`
+///
+/// See `DebugStruct` for details.
+#[allow(missing_debug_implementations)]
+pub struct DebugTuple<'a, 'b> {
+    builder: Builder<'a, 'b>,
+}
+
+/// A `debug_list` builder that nests entries through `Indented`
+///
+/// See `DebugStruct` for details.
+#[allow(missing_debug_implementations)]
+pub struct DebugList<'a, 'b> {
+    builder: Builder<'a, 'b>,
+}
+
+/// Create a `DebugStruct` builder for a struct called `name`
+pub fn debug_struct<'a, 'b>(f: &'a mut fmt::Formatter<'b>, name: &str) -> DebugStruct<'a, 'b> {
+    let result = f.write_str(name);
+
+    DebugStruct {
+        builder: Builder::new(f, result),
+    }
+}
+
+/// Create a `DebugTuple` builder for a tuple struct called `name`
+pub fn debug_tuple<'a, 'b>(f: &'a mut fmt::Formatter<'b>, name: &str) -> DebugTuple<'a, 'b> {
+    let result = f.write_str(name);
+
+    DebugTuple {
+        builder: Builder::new(f, result),
+    }
+}
+
+/// Create a `DebugList` builder
+pub fn debug_list<'a, 'b>(f: &'a mut fmt::Formatter<'b>) -> DebugList<'a, 'b> {
+    let result = f.write_str("[");
+
+    DebugList {
+        builder: Builder::new(f, result),
+    }
+}
+
+struct Builder<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    indentation: &'static str,
+    result: fmt::Result,
+    has_fields: bool,
+}
+
+impl<'a, 'b> Builder<'a, 'b> {
+    fn new(f: &'a mut fmt::Formatter<'b>, result: fmt::Result) -> Self {
+        Self {
+            f,
+            indentation: "    ",
+            result,
+            has_fields: false,
+        }
+    }
+
+    /// Write one field, `open` is written before the first field and a comma before the others
+    fn field(&mut self, open: &str, name: Option<&str>, value: &dyn fmt::Debug) -> fmt::Result {
+        self.result?;
+
+        if self.f.alternate() {
+            if !self.has_fields {
+                self.f.write_str(open.trim_end())?;
+                self.f.write_char('\n')?;
+            }
+
+            let mut indented = indented(self.f).with_str(self.indentation);
+            if let Some(name) = name {
+                write!(indented, "{}: ", name)?;
+            }
+            writeln!(indented, "{:#?},", value)
+        } else {
+            self.f
+                .write_str(if self.has_fields { ", " } else { open })?;
+            if let Some(name) = name {
+                write!(self.f, "{}: ", name)?;
+            }
+            value.fmt(self.f)
+        }
+    }
+
+    fn record(&mut self, open: &str, name: Option<&str>, value: &dyn fmt::Debug) {
+        self.result = self.field(open, name, value);
+        self.has_fields = true;
+    }
+
+    fn finish(&mut self, close: &str) -> fmt::Result {
+        self.result?;
+
+        if self.f.alternate() {
+            self.f.write_str(close.trim_start())
+        } else {
+            self.f.write_str(close)
+        }
+    }
+}
+
+impl<'a, 'b> DebugStruct<'a, 'b> {
+    /// Sets the string used to indent fields when pretty printing
+    pub fn with_str(mut self, indentation: &'static str) -> Self {
+        self.builder.indentation = indentation;
+        self
+    }
+
+    /// Adds a new field to the generated struct output
+    pub fn field(&mut self, name: &str, value: &dyn fmt::Debug) -> &mut Self {
+        self.builder.record(" { ", Some(name), value);
+        self
+    }
+
+    /// Finishes output and returns any error encountered
+    pub fn finish(&mut self) -> fmt::Result {
+        if self.builder.has_fields {
+            self.builder.finish(" }")
+        } else {
+            self.builder.result
+        }
+    }
+}
+
+impl<'a, 'b> DebugTuple<'a, 'b> {
+    /// Sets the string used to indent fields when pretty printing
+    pub fn with_str(mut self, indentation: &'static str) -> Self {
+        self.builder.indentation = indentation;
+        self
+    }
+
+    /// Adds a new field to the generated tuple struct output
+    pub fn field(&mut self, value: &dyn fmt::Debug) -> &mut Self {
+        self.builder.record("(", None, value);
+        self
+    }
+
+    /// Finishes output and returns any error encountered
+    pub fn finish(&mut self) -> fmt::Result {
+        if self.builder.has_fields {
+            self.builder.finish(")")
+        } else {
+            self.builder.result
+        }
+    }
+}
+
+impl<'a, 'b> DebugList<'a, 'b> {
+    /// Sets the string used to indent entries when pretty printing
+    pub fn with_str(mut self, indentation: &'static str) -> Self {
+        self.builder.indentation = indentation;
+        self
+    }
+
+    /// Adds a new entry to the list output
+    pub fn entry(&mut self, value: &dyn fmt::Debug) -> &mut Self {
+        self.builder.record("", None, value);
+        self
+    }
+
+    /// Adds the contents of an iterator of entries to the list output
+    pub fn entries<D, I>(&mut self, entries: I) -> &mut Self
+    where
+        D: fmt::Debug,
+        I: IntoIterator<Item = D>,
+    {
+        for entry in entries {
+            self.entry(&entry);
+        }
+
+        self
+    }
+
+    /// Finishes output and returns any error encountered
+    pub fn finish(&mut self) -> fmt::Result {
+        self.builder.finish("]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::format;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    struct Node {
+        name: &'static str,
+        children: Vec<Node>,
+    }
+
+    impl fmt::Debug for Node {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            debug_tuple(f, "Node")
+                .with_str("  ")
+                .field(&self.name)
+                .field(&Children(&self.children))
+                .finish()
+        }
+    }
+
+    struct Children<'a>(&'a [Node]);
+
+    impl fmt::Debug for Children<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            debug_list(f).with_str("  ").entries(self.0).finish()
+        }
+    }
+
+    fn tree() -> Node {
+        Node {
+            name: "root",
+            children: vec![Node {
+                name: "leaf",
+                children: vec![],
+            }],
+        }
+    }
+
+    #[test]
+    fn single_line() {
+        assert_eq!(
+            format!("{:?}", tree()),
+            "Node(\"root\", [Node(\"leaf\", [])])"
+        );
+    }
+
+    #[test]
+    fn nested_pretty() {
+        let expected =
+            "Node(\n  \"root\",\n  [\n    Node(\n      \"leaf\",\n      [],\n    ),\n  ],\n)";
+
+        assert_eq!(format!("{:#?}", tree()), expected);
+    }
+
+    #[test]
+    fn empty_struct() {
+        struct Unit;
+
+        impl fmt::Debug for Unit {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                debug_struct(f, "Unit").finish()
+            }
+        }
+
+        assert_eq!(format!("{:#?}", Unit), "Unit");
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{indented, wrap, Format, Indent, Indentation};
+use core::fmt::{self, Write};
+use core::mem;
+
+/// A writer for definition lists, with each description wrapped and indented under its term
+///
+/// # Explanation
+///
+/// Configuration dumps and `--explain` style output list a series of terms, each followed by a
+/// longer description. `entry` writes the term on a line of its own and the description beneath
+/// it, word wrapped to the configured width and indented by `indent` columns. Entries are
+/// separated by `spacing` blank lines.
+///
+/// ```rust
+/// use indenter::DefinitionList;
+///
+/// let mut output = String::new();
+/// let mut list = DefinitionList::new(&mut output, 28);
+/// list.entry("jobs", "Number of parallel jobs to run, defaults to the CPU count")?;
+/// list.entry("offline", "Never access the network")?;
+///
+/// assert_eq!(
+///     output,
+///     "\
+/// jobs
+///     Number of parallel jobs
+///     to run, defaults to the
+///     CPU count
+///
+/// offline
+///     Never access the network
+/// "
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct DefinitionList<'a> {
+    inner: &'a mut dyn fmt::Write,
+    width: usize,
+    indent: usize,
+    spacing: usize,
+    started: bool,
+}
+
+impl<'a> DefinitionList<'a> {
+    /// Construct a list wrapped to `width` columns, indenting descriptions by four columns and
+    /// separating entries with one blank line
+    pub fn new(inner: &'a mut dyn fmt::Write, width: usize) -> Self {
+        Self {
+            inner,
+            width,
+            indent: 4,
+            spacing: 1,
+            started: false,
+        }
+    }
+
+    /// Sets the number of columns descriptions are indented by
+    pub fn with_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sets the number of blank lines between entries
+    pub fn with_spacing(mut self, spacing: usize) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Write `term` followed by its wrapped and indented `description`
+    pub fn entry(&mut self, term: impl fmt::Display, description: &str) -> fmt::Result {
+        if mem::replace(&mut self.started, true) {
+            for _ in 0..self.spacing {
+                self.inner.write_char('\n')?;
+            }
+        }
+
+        writeln!(self.inner, "{}", term)?;
+
+        let indent = Indentation::new(Indent(self.indent), " ");
+        let mut inserter = |_, f: &mut dyn Write| write!(f, "{}", indent);
+        let mut indented = indented(&mut *self.inner).with_format(Format::Custom {
+            inserter: &mut inserter,
+        });
+        let width = self.width.saturating_sub(self.indent).max(1);
+
+        writeln!(indented, "{}", wrap(description, width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn compact_entries() {
+        let mut output = String::new();
+        let mut list = DefinitionList::new(&mut output, 80)
+            .with_indent(2)
+            .with_spacing(0);
+        list.entry("a", "first").unwrap();
+        list.entry("b", "second\n\nparagraph").unwrap();
+
+        assert_eq!(output, "a\n  first\nb\n  second\n\n  paragraph\n");
+    }
+}
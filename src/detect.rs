@@ -0,0 +1,115 @@
+use crate::spaces::{SPACES, TABS};
+use core::fmt;
+
+/// The unit a block of text is indented by, as guessed by `detect_indent`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndentUnit {
+    /// One tab per level
+    Tabs,
+    /// The given number of spaces per level
+    Spaces(usize),
+}
+
+impl IndentUnit {
+    /// Returns one level of this unit as a string, for use with `Indented::with_str`
+    ///
+    /// Units of more than 64 spaces are cut short to 64.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IndentUnit::Tabs => &TABS[..1],
+            IndentUnit::Spaces(width) => &SPACES[..width.min(SPACES.len())],
+        }
+    }
+}
+
+impl fmt::Display for IndentUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Guess the unit `text` is indented by
+///
+/// # Explanation
+///
+/// Lines that start with a tab are counted against lines that start with spaces, and the more
+/// common of the two wins. For spaces, the width is the step by which the indentation most often
+/// grows from one line to the next, so a few deeply aligned continuation lines do not throw the
+/// guess off. Blank lines are ignored. Returns `None` if no line is indented.
+///
+/// The result can drive the rest of this crate, for example converting text to another unit
+/// with `Reindent`:
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{detect_indent, IndentUnit, Reindent};
+///
+/// let text = "fn main() {\n  if true {\n    run();\n  }\n}";
+/// let unit = detect_indent(text);
+/// assert_eq!(unit, Some(IndentUnit::Spaces(2)));
+///
+/// let mut output = String::new();
+/// let width = match unit {
+///     Some(IndentUnit::Spaces(width)) => width,
+///     _ => 4,
+/// };
+/// write!(Reindent::new(&mut output, "\t").from_width(width), "{}", text)?;
+///
+/// assert_eq!(output, "fn main() {\n\tif true {\n\t\trun();\n\t}\n}");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+pub fn detect_indent(text: &str) -> Option<IndentUnit> {
+    let mut tabs = 0;
+    let mut spaces = 0;
+    let mut steps = [0usize; 9];
+    let mut previous = 0;
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.starts_with('\t') {
+            tabs += 1;
+            previous = 0;
+            continue;
+        }
+
+        let width = line.len() - line.trim_start_matches(' ').len();
+        if width > 0 {
+            spaces += 1;
+        }
+        if width > previous && width - previous < steps.len() {
+            steps[width - previous] += 1;
+        }
+        previous = width;
+    }
+
+    if tabs > 0 && tabs >= spaces {
+        return Some(IndentUnit::Tabs);
+    }
+
+    let (width, count) = steps
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|&(_, &count)| count)?;
+    if *count == 0 {
+        return None;
+    }
+
+    Some(IndentUnit::Spaces(width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_common_step_wins() {
+        let text = "a\n    b\n        c\n                  aligned\n    d\n\n\te";
+        assert_eq!(detect_indent(text), Some(IndentUnit::Spaces(4)));
+        assert_eq!(detect_indent("a\n\tb\n\t\tc"), Some(IndentUnit::Tabs));
+        assert_eq!(detect_indent("a\n\nb"), None);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{indented, Format, Indent, Indentation};
+use core::fmt::{self, Write};
+
+/// Helper struct for displaying a message with a label in the layout of rustc's diagnostics
+///
+/// # Explanation
+///
+/// rustc starts a diagnostic with its label, such as `error[E0308]:` or `help:`, and aligns the
+/// continuation lines of the message under its first character. Notes attached to a code snippet
+/// are written as `= note:` below the snippet's `|` gutter instead, which `attached` reproduces
+/// given the width of the line number column. The widths are measured from the label, so tools
+/// that emit diagnostics don't have to count them by hand.
+///
+/// ```rust
+/// use indenter::Diagnostic;
+///
+/// assert_eq!(
+///     Diagnostic::error("mismatched types\nin this call")
+///         .with_code("E0308")
+///         .to_string(),
+///     "error[E0308]: mismatched types\n              in this call"
+/// );
+/// assert_eq!(
+///     Diagnostic::note("expected `u32`\n   found `i32`")
+///         .attached(2)
+///         .to_string(),
+///     "   = note: expected `u32`\n              found `i32`"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostic<'a, T> {
+    kind: &'a str,
+    code: Option<&'a str>,
+    margin: Option<usize>,
+    message: T,
+}
+
+impl<'a, T: fmt::Display> Diagnostic<'a, T> {
+    /// Construct a diagnostic labelled `kind:`
+    pub fn new(kind: &'a str, message: T) -> Self {
+        Self {
+            kind,
+            code: None,
+            margin: None,
+            message,
+        }
+    }
+
+    /// Construct an `error:` diagnostic
+    pub fn error(message: T) -> Self {
+        Self::new("error", message)
+    }
+
+    /// Construct a `warning:` diagnostic
+    pub fn warning(message: T) -> Self {
+        Self::new("warning", message)
+    }
+
+    /// Construct a `note:` diagnostic
+    pub fn note(message: T) -> Self {
+        Self::new("note", message)
+    }
+
+    /// Construct a `help:` diagnostic
+    pub fn help(message: T) -> Self {
+        Self::new("help", message)
+    }
+
+    /// Sets the code written in brackets after the kind, as in `error[E0308]:`
+    pub fn with_code(mut self, code: &'a str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Write the label as `= kind:` under a snippet whose line numbers are `margin` columns wide
+    pub fn attached(mut self, margin: usize) -> Self {
+        self.margin = Some(margin);
+        self
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Diagnostic<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut width = 0;
+        if let Some(margin) = self.margin {
+            write!(f, "{} = ", Indentation::new(Indent(margin), " "))?;
+            width += margin + 3;
+        }
+
+        f.write_str(self.kind)?;
+        width += self.kind.chars().count();
+        if let Some(code) = self.code {
+            write!(f, "[{}]", code)?;
+            width += code.chars().count() + 2;
+        }
+        f.write_str(": ")?;
+        width += 2;
+
+        let indentation = Indentation::new(Indent(width), " ");
+        let mut inserter = move |_, f: &mut dyn Write| write!(f, "{}", indentation);
+
+        write!(
+            indented(f)
+                .with_format(Format::Custom {
+                    inserter: &mut inserter,
+                })
+                .skip_lines(1),
+            "{}",
+            self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn help_with_blank_line() {
+        assert_eq!(
+            Diagnostic::help("remove the borrow\n\nlike this").to_string(),
+            "help: remove the borrow\n\n      like this"
+        );
+    }
+}
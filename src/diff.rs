@@ -0,0 +1,148 @@
+use crate::indented;
+use core::fmt::{self, Write};
+
+/// A line of an already computed diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffLine<'a> {
+    /// A line both sides have in common
+    Context(&'a str),
+    /// A line only the new side has
+    Added(&'a str),
+    /// A line only the old side has
+    Removed(&'a str),
+}
+
+impl<'a> DiffLine<'a> {
+    /// Returns the prefix of the line in a unified diff
+    fn prefix(self) -> char {
+        match self {
+            DiffLine::Context(_) => ' ',
+            DiffLine::Added(_) => '+',
+            DiffLine::Removed(_) => '-',
+        }
+    }
+
+    /// Returns the text of the line
+    fn text(self) -> &'a str {
+        match self {
+            DiffLine::Context(text) | DiffLine::Added(text) | DiffLine::Removed(text) => text,
+        }
+    }
+}
+
+/// A writer for the hunks of a unified diff
+///
+/// # Explanation
+///
+/// Computing a diff is left to a diffing crate, and this writer lays the result out the way
+/// `diff -u` and `git diff` do, so the output can be read by people and by `patch` alike. Each
+/// hunk starts with an `@@ -start,count +start,count @@` header, where the counts are taken from
+/// the lines of the hunk and left out when they are 1, and the start of a side with no lines is
+/// the line before the hunk. The whole hunk can be indented, to nest it in an assertion failure
+/// or a report.
+///
+/// ```rust
+/// use indenter::{DiffLine, HunkWriter};
+///
+/// let mut output = String::new();
+/// let mut diff = HunkWriter::new(&mut output).with_str("    ");
+/// diff.hunk(
+///     3,
+///     3,
+///     &[
+///         DiffLine::Context("[server]"),
+///         DiffLine::Removed("port = 80"),
+///         DiffLine::Added("port = 8080"),
+///         DiffLine::Added("host = \"::\""),
+///     ],
+/// )?;
+///
+/// assert_eq!(
+///     output,
+///     "    @@ -3,2 +3,3 @@\n     [server]\n    -port = 80\n    +port = 8080\n    +host = \"::\"\n"
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct HunkWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    indentation: &'static str,
+}
+
+impl<'a> HunkWriter<'a> {
+    /// Construct a writer that doesn't indent its hunks
+    pub fn new(inner: &'a mut dyn fmt::Write) -> Self {
+        Self {
+            inner,
+            indentation: "",
+        }
+    }
+
+    /// Sets the string every line of a hunk is indented with
+    pub fn with_str(mut self, indentation: &'static str) -> Self {
+        self.indentation = indentation;
+        self
+    }
+
+    /// Write a hunk whose first line is line `old_start` of the old side and `new_start` of the
+    /// new side, counting from 1
+    pub fn hunk(
+        &mut self,
+        old_start: usize,
+        new_start: usize,
+        lines: &[DiffLine<'_>],
+    ) -> fmt::Result {
+        let old = lines
+            .iter()
+            .filter(|line| !matches!(line, DiffLine::Added(_)))
+            .count();
+        let new = lines
+            .iter()
+            .filter(|line| !matches!(line, DiffLine::Removed(_)))
+            .count();
+
+        let mut indented = indented(&mut *self.inner).with_str(self.indentation);
+        writeln!(
+            indented,
+            "@@ -{} +{} @@",
+            Range(old_start, old),
+            Range(new_start, new)
+        )?;
+        for line in lines {
+            writeln!(indented, "{}{}", line.prefix(), line.text())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The range of one side of a hunk, as written in its header
+struct Range(usize, usize);
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Range(start, 0) => write!(f, "{},0", start.saturating_sub(1)),
+            Range(start, 1) => write!(f, "{}", start),
+            Range(start, count) => write!(f, "{},{}", start, count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn empty_sides() {
+        let mut output = String::new();
+        let mut diff = HunkWriter::new(&mut output);
+        diff.hunk(1, 1, &[DiffLine::Added("first")]).unwrap();
+        diff.hunk(5, 5, &[DiffLine::Removed("a"), DiffLine::Removed("b")])
+            .unwrap();
+
+        assert_eq!(output, "@@ -0,0 +1 @@\n+first\n@@ -5,2 +4,0 @@\n-a\n-b\n");
+    }
+}
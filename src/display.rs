@@ -0,0 +1,379 @@
+use crate::{indented, Format};
+use core::cell::{Cell, RefCell};
+use core::fmt::{self, Write};
+
+/// Helper struct for indenting a `Display` value inside ordinary format strings
+///
+/// # Explanation
+///
+/// This type implements `Display` by writing the wrapped value through an `Indented` that wraps
+/// the outer formatter, so the indentation is applied while the value is being formatted rather
+/// than to an intermediate `String`.
+///
+/// A precision in the format string limits the number of lines that are written. Once the limit
+/// is reached any remaining output is replaced by a single `...` line.
+///
+/// ```rust
+/// use indenter::indent_display;
+///
+/// let output = format!("error:\n{}", indent_display("first\nsecond", "  "));
+///
+/// assert_eq!(output, "error:\n  first\n  second");
+///
+/// let output = format!("{:.2}", indent_display("first\nsecond\nthird", "  "));
+///
+/// assert_eq!(output, "  first\n  second\n  ...");
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct IndentDisplay<'a, T> {
+    value: T,
+    format: Cell<Option<Format<'a>>>,
+    fill_width: bool,
+}
+
+/// Helper function for indenting a `Display` value with a static string
+pub fn indent_display<T: fmt::Display>(
+    value: T,
+    indentation: &'static str,
+) -> IndentDisplay<'static, T> {
+    indent_display_with(value, Format::Uniform { indentation })
+}
+
+/// Helper function for indenting a `Display` value with a user defined format
+pub fn indent_display_with<T: fmt::Display>(value: T, format: Format<'_>) -> IndentDisplay<'_, T> {
+    IndentDisplay {
+        value,
+        format: Cell::new(Some(format)),
+        fill_width: false,
+    }
+}
+
+impl<T> IndentDisplay<'_, T> {
+    /// Derive the indentation from the width and fill of the outer format string
+    ///
+    /// When a width is given, for example `{:>8}` or `{:-<2}`, every line is indented with that
+    /// many fill characters instead of the configured format. Without a width the configured
+    /// format is used as usual.
+    ///
+    /// ```rust
+    /// use indenter::indent_display;
+    ///
+    /// let adapter = indent_display("a\nb", "    ").fill_width();
+    ///
+    /// assert_eq!(format!("{:2}", adapter), "  a\n  b");
+    /// assert_eq!(format!("{:.>3}", adapter), "...a\n...b");
+    /// assert_eq!(format!("{}", adapter), "    a\n    b");
+    /// ```
+    pub fn fill_width(mut self) -> Self {
+        self.fill_width = true;
+        self
+    }
+}
+
+/// Helper struct for indenting a `Debug` value inside ordinary format strings
+///
+/// # Explanation
+///
+/// This is the `Debug` counterpart of `IndentDisplay`. The alternate flag of the outer formatter
+/// is forwarded, so `{:#?}` pretty prints the wrapped value before it is indented.
+///
+/// ```rust
+/// use indenter::indent_debug;
+///
+/// let output = format!("{:#?}", indent_debug(Some(1), "  "));
+///
+/// assert_eq!(output, "  Some(\n      1,\n  )");
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct IndentDebug<'a, T> {
+    value: T,
+    format: Cell<Option<Format<'a>>>,
+}
+
+/// Helper function for indenting a `Debug` value with a static string
+pub fn indent_debug<T: fmt::Debug>(value: T, indentation: &'static str) -> IndentDebug<'static, T> {
+    indent_debug_with(value, Format::Uniform { indentation })
+}
+
+/// Helper function for indenting a `Debug` value with a user defined format
+pub fn indent_debug_with<T: fmt::Debug>(value: T, format: Format<'_>) -> IndentDebug<'_, T> {
+    IndentDebug {
+        value,
+        format: Cell::new(Some(format)),
+    }
+}
+
+/// Helper struct for indenting the output of a closure
+///
+/// # Explanation
+///
+/// This type implements `Display` by calling the wrapped closure with a formatter whose output is
+/// routed through an `Indented`. It lets indented sub-sections be built inline without defining a
+/// one-off newtype for each of them. Like `IndentDisplay`, a precision limits the number of
+/// lines written.
+///
+/// ```rust
+/// use indenter::indent_fn;
+///
+/// let items = ["first", "second"];
+/// let section = indent_fn(|f| {
+///     for item in &items {
+///         writeln!(f, "- {}", item)?;
+///     }
+///
+///     Ok(())
+/// })
+/// .with_str("    ");
+///
+/// assert_eq!(format!("items:\n{}", section), "items:\n    - first\n    - second\n");
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct IndentFn<'a, F> {
+    func: RefCell<F>,
+    format: Cell<Option<Format<'a>>>,
+}
+
+/// Helper function for indenting the output of a closure with the default indentation
+pub fn indent_fn<F>(func: F) -> IndentFn<'static, F>
+where
+    F: FnMut(&mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    IndentFn {
+        func: RefCell::new(func),
+        format: Cell::new(Some(Format::DEFAULT)),
+    }
+}
+
+impl<'a, F> IndentFn<'a, F> {
+    /// Sets the format to `Format::Numbered` with the provided index
+    pub fn ind(self, ind: usize) -> Self {
+        self.with_format(Format::Numbered { ind })
+    }
+
+    /// Sets the format to `Format::Uniform` with the provided static string
+    pub fn with_str(self, indentation: &'static str) -> Self {
+        self.with_format(Format::Uniform { indentation })
+    }
+
+    /// Use a user defined format for the closure's output
+    pub fn with_format<'b>(self, format: Format<'b>) -> IndentFn<'b, F> {
+        IndentFn {
+            func: self.func,
+            format: Cell::new(Some(format)),
+        }
+    }
+}
+
+struct Call<'f, F>(&'f RefCell<F>);
+
+impl<F> fmt::Display for Call<'_, F>
+where
+    F: FnMut(&mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut func = self.0.try_borrow_mut().map_err(|_| fmt::Error)?;
+        (*func)(f)
+    }
+}
+
+impl<F> fmt::Display for IndentFn<'_, F>
+where
+    F: FnMut(&mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = f.precision();
+        write_indented(f, &self.format, limit, format_args!("{}", Call(&self.func)))
+    }
+}
+
+/// Extension trait for indenting any `Display` value in place
+///
+/// This trait is implemented for every `Display` type and returns the same adapters as
+/// `indent_display` and `indent_display_with`, so call sites can read left to right.
+///
+/// ```rust
+/// use indenter::DisplayExt;
+///
+/// let error = "first\nsecond";
+/// let output = format!("{}\n{}", "error:", error.indented("  "));
+///
+/// assert_eq!(output, "error:\n  first\n  second");
+/// assert_eq!(error.numbered(1).to_string(), "   1: first\n      second");
+/// ```
+pub trait DisplayExt: fmt::Display {
+    /// Indent every line of this value with a static string
+    fn indented(&self, indentation: &'static str) -> IndentDisplay<'static, &Self> {
+        indent_display(self, indentation)
+    }
+
+    /// Indent this value using `Format::Numbered` with the provided index
+    fn numbered(&self, ind: usize) -> IndentDisplay<'static, &Self> {
+        indent_display_with(self, Format::Numbered { ind })
+    }
+}
+
+impl<T: fmt::Display + ?Sized> DisplayExt for T {}
+
+/// Writes `args` to `f` through an indenter using the format stored in `cell`
+///
+/// The format is moved out of the cell for the duration of the write so that custom inserters
+/// can be called mutably from behind `&self`, and is put back afterwards. When `limit` is set no
+/// more than that many lines are written.
+fn write_indented(
+    f: &mut fmt::Formatter<'_>,
+    cell: &Cell<Option<Format<'_>>>,
+    limit: Option<usize>,
+    args: fmt::Arguments<'_>,
+) -> fmt::Result {
+    let mut format = cell.take().ok_or(fmt::Error)?;
+    let mut indented = indented(f).with_format(format.reborrow());
+    let result = match limit {
+        Some(remaining) => LineLimit {
+            inner: &mut indented,
+            remaining,
+            truncated: false,
+        }
+        .write_fmt(args),
+        None => indented.write_fmt(args),
+    };
+    cell.set(Some(format));
+    result
+}
+
+/// Writer that passes through a fixed number of lines and replaces the rest with `...`
+struct LineLimit<W> {
+    inner: W,
+    remaining: usize,
+    truncated: bool,
+}
+
+impl<W: Write> Write for LineLimit<W> {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        while !s.is_empty() && !self.truncated {
+            if self.remaining == 0 {
+                self.truncated = true;
+                return self.inner.write_str("...");
+            }
+
+            match s.find('\n') {
+                Some(newline) => {
+                    self.inner.write_str(&s[..=newline])?;
+                    self.remaining -= 1;
+                    s = &s[newline + 1..];
+                }
+                None => return self.inner.write_str(s),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for IndentDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = f.precision();
+
+        if let (true, Some(width)) = (self.fill_width, f.width()) {
+            let fill = f.fill();
+            let mut inserter = move |_, f: &mut dyn Write| {
+                for _ in 0..width {
+                    f.write_char(fill)?;
+                }
+
+                Ok(())
+            };
+            let format = Cell::new(Some(Format::Custom {
+                inserter: &mut inserter,
+            }));
+
+            return write_indented(f, &format, limit, format_args!("{}", self.value));
+        }
+
+        write_indented(f, &self.format, limit, format_args!("{}", self.value))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IndentDebug<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write_indented(f, &self.format, None, format_args!("{:#?}", self.value))
+        } else {
+            write_indented(f, &self.format, None, format_args!("{:?}", self.value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[test]
+    fn uniform() {
+        let output = indent_display("verify\nthis", "  ").to_string();
+
+        assert_eq!(output, "  verify\n  this");
+    }
+
+    #[test]
+    fn numbered_twice() {
+        let adapter = indent_display_with("verify\nthis", Format::Numbered { ind: 3 });
+
+        assert_eq!(adapter.to_string(), "   3: verify\n      this");
+        assert_eq!(adapter.to_string(), "   3: verify\n      this");
+    }
+
+    #[test]
+    fn debug_forwards_alternate() {
+        let value = (1, "two");
+
+        assert_eq!(format!("{:?}", indent_debug(value, "> ")), "> (1, \"two\")");
+        assert_eq!(
+            format!("{:#?}", indent_debug(value, "> ")),
+            "> (\n>     1,\n>     \"two\",\n> )"
+        );
+    }
+
+    #[test]
+    fn extension_trait() {
+        let value = 42;
+
+        assert_eq!(format!("[{}]", value.indented("  ")), "[  42]");
+        assert_eq!(value.numbered(12).to_string(), "  12: 42");
+    }
+
+    #[test]
+    fn closure() {
+        let mut calls = 0;
+        let adapter = indent_fn(|f| {
+            calls += 1;
+            write!(f, "call\n{}", calls)
+        })
+        .with_str("  ");
+
+        assert_eq!(adapter.to_string(), "  call\n  1");
+        assert_eq!(adapter.to_string(), "  call\n  2");
+    }
+
+    #[test]
+    fn precision_limits_lines() {
+        let input = "one\ntwo\n";
+
+        assert_eq!(format!("{:.0}", indent_display(input, "  ")), "  ...");
+        assert_eq!(
+            format!("{:.1}", indent_display(input, "  ")),
+            "  one\n  ..."
+        );
+        assert_eq!(
+            format!("{:.2}", indent_display(input, "  ")),
+            "  one\n  two\n"
+        );
+        assert_eq!(
+            format!("{:.5}", indent_display(input, "  ")),
+            "  one\n  two\n"
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{IndentOptions, IndentUnit, LineEnding};
+use std::io;
+use std::path::{Component, Path};
+
+impl IndentOptions {
+    /// Load the settings an `.editorconfig` gives for the file at `path`
+    ///
+    /// # Explanation
+    ///
+    /// Every `.editorconfig` from the directory of `path` up to the first one with `root = true`
+    /// is read, and the sections whose glob matches `path` are applied, with files closer to
+    /// `path` and later sections taking precedence, as described at <https://editorconfig.org>.
+    /// The file at `path` itself does not need to exist, so the settings can be looked up before
+    /// generating it. Settings that are not given keep their default, and unknown properties and
+    /// lines are ignored. This is behind the `std` feature.
+    ///
+    /// The properties used are `indent_style`, `indent_size`, `tab_width`, `end_of_line` and
+    /// `insert_final_newline`.
+    ///
+    /// ```rust,no_run
+    /// use core::fmt::Write;
+    /// use indenter::IndentOptions;
+    ///
+    /// let options = IndentOptions::from_editorconfig("src/generated.rs")?;
+    ///
+    /// let mut output = String::new();
+    /// writeln!(output, "impl Generated {{")?;
+    /// writeln!(options.indented(&mut output), "fn new() -> Self {{ Self }}")?;
+    /// writeln!(output, "}}")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_editorconfig(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = std::env::current_dir()?.join(path);
+
+        let mut files = Vec::new();
+        for dir in path.ancestors().skip(1) {
+            let contents = match std::fs::read_to_string(dir.join(".editorconfig")) {
+                Ok(contents) => contents,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+
+            let root = parse(&contents).any(|(section, key, value)| {
+                section.is_none() && key == "root" && value.eq_ignore_ascii_case("true")
+            });
+            files.push((dir, contents));
+
+            if root {
+                break;
+            }
+        }
+
+        let mut settings = Settings::default();
+        for (dir, contents) in files.iter().rev() {
+            let Some(relative) = relative_path(&path, dir) else {
+                continue;
+            };
+
+            for (section, key, value) in parse(contents) {
+                if section.is_some_and(|glob| matches_section(glob, &relative)) {
+                    settings.set(&key, value);
+                }
+            }
+        }
+
+        Ok(settings.finish())
+    }
+}
+
+/// The properties read so far, before they are resolved into `IndentOptions`
+#[derive(Default)]
+struct Settings<'a> {
+    style: Option<&'a str>,
+    size: Option<&'a str>,
+    tab_width: Option<&'a str>,
+    options: IndentOptions,
+}
+
+impl<'a> Settings<'a> {
+    fn set(&mut self, key: &str, value: &'a str) {
+        match key {
+            "indent_style" => self.style = Some(value),
+            "indent_size" => self.size = Some(value),
+            "tab_width" => self.tab_width = Some(value),
+            "end_of_line" => {
+                self.options.line_ending = match &*value.to_ascii_lowercase() {
+                    "lf" => LineEnding::Lf,
+                    "crlf" => LineEnding::CrLf,
+                    "cr" => LineEnding::Cr,
+                    _ => return,
+                }
+            }
+            "insert_final_newline" => {
+                if let Ok(final_newline) = value.to_ascii_lowercase().parse() {
+                    self.options.final_newline = final_newline;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> IndentOptions {
+        let size = self
+            .size
+            .filter(|size| !size.eq_ignore_ascii_case("tab"))
+            .or(self.tab_width)
+            .and_then(|size| size.parse().ok());
+
+        // An explicit `space` style means spaces even without a size, whatever the default unit is
+        match self.style {
+            Some(style) if style.eq_ignore_ascii_case("tab") => {
+                self.options.unit = IndentUnit::Tabs;
+            }
+            Some(style) if style.eq_ignore_ascii_case("space") => {
+                self.options.unit = IndentUnit::Spaces(size.unwrap_or(4));
+            }
+            _ => {
+                if let Some(size) = size {
+                    self.options.unit = IndentUnit::Spaces(size);
+                }
+            }
+        }
+
+        self.options
+    }
+}
+
+/// Parses the lines of an `.editorconfig` into the glob of their section, which is `None` before
+/// the first section, and a lowercase key and value
+fn parse(contents: &str) -> impl Iterator<Item = (Option<&str>, String, &str)> {
+    let mut section = None;
+    contents.lines().filter_map(move |line| {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            section = Some(&line[1..line.len() - 1]);
+            return None;
+        }
+
+        if line.starts_with(['#', ';']) {
+            return None;
+        }
+
+        let (key, value) = line.split_once('=')?;
+        Some((section, key.trim().to_ascii_lowercase(), value.trim()))
+    })
+}
+
+/// Returns `path` relative to `dir` with `/` separators, as section globs are written
+fn relative_path(path: &Path, dir: &Path) -> Option<Vec<char>> {
+    let mut relative = Vec::new();
+    for component in path.strip_prefix(dir).ok()?.components() {
+        if let Component::Normal(name) = component {
+            if !relative.is_empty() {
+                relative.push('/');
+            }
+            relative.extend(name.to_str()?.chars());
+        }
+    }
+
+    Some(relative)
+}
+
+/// Whether the glob of a section matches a path relative to its `.editorconfig`
+///
+/// Globs without a `/` match the file name in any directory, the others match from the directory
+/// of the `.editorconfig`.
+fn matches_section(glob: &str, relative: &[char]) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    if glob.contains(&'/') {
+        let glob = glob.strip_prefix(&['/']).unwrap_or(&glob);
+        return matches(glob, relative);
+    }
+
+    let name = match relative.iter().rposition(|&c| c == '/') {
+        Some(slash) => &relative[slash + 1..],
+        None => relative,
+    };
+    matches(&glob, name)
+}
+
+/// Matches `path` against an EditorConfig glob, supporting `*`, `**`, `?`, `[...]` and `{a,b}`
+fn matches(glob: &[char], path: &[char]) -> bool {
+    let Some((&first, rest)) = glob.split_first() else {
+        return path.is_empty();
+    };
+
+    match first {
+        '*' if rest.first() == Some(&'*') => {
+            (0..=path.len()).any(|i| matches(&rest[1..], &path[i..]))
+        }
+        '*' => {
+            for i in 0..=path.len() {
+                if matches(rest, &path[i..]) {
+                    return true;
+                }
+                if path.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        '?' => match path.split_first() {
+            Some((&c, path)) => c != '/' && matches(rest, path),
+            None => false,
+        },
+        '[' if rest.contains(&']') => {
+            let end = rest.iter().position(|&c| c == ']').unwrap();
+            let (negated, class) = match rest[..end].split_first() {
+                Some((&'!', class)) => (true, class),
+                _ => (false, &rest[..end]),
+            };
+
+            match path.split_first() {
+                Some((&c, path)) => {
+                    c != '/' && in_class(class, c) != negated && matches(&rest[end + 1..], path)
+                }
+                None => false,
+            }
+        }
+        '{' if rest.contains(&'}') => {
+            let end = rest.iter().position(|&c| c == '}').unwrap();
+            rest[..end].split(|&c| c == ',').any(|alternative| {
+                let mut glob = alternative.to_vec();
+                glob.extend_from_slice(&rest[end + 1..]);
+                matches(&glob, path)
+            })
+        }
+        '\\' if !rest.is_empty() => {
+            path.first() == Some(&rest[0]) && matches(&rest[1..], &path[1..])
+        }
+        c => path.first() == Some(&c) && matches(rest, &path[1..]),
+    }
+}
+
+/// Whether `c` is one of the characters or ranges of a `[...]` class
+fn in_class(class: &[char], c: char) -> bool {
+    let mut i = 0;
+    while i < class.len() {
+        if class.get(i + 1) == Some(&'-') && i + 2 < class.len() {
+            if (class[i]..=class[i + 2]).contains(&c) {
+                return true;
+            }
+            i += 3;
+        } else {
+            if class[i] == c {
+                return true;
+            }
+            i += 1;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn globs() {
+        let path: Vec<char> = "src/gen/out.rs".chars().collect();
+        assert!(matches_section("*.rs", &path));
+        assert!(matches_section("*.{md,rs}", &path));
+        assert!(matches_section("/src/**.rs", &path));
+        assert!(matches_section("[!a-n]ut.r?", &path));
+        assert!(!matches_section("/*.rs", &path));
+        assert!(!matches_section("src/*.rs", &path));
+    }
+
+    #[test]
+    fn nested_files_take_precedence() {
+        let root =
+            std::env::temp_dir().join(format!("indenter-editorconfig-{}", std::process::id()));
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(
+            root.join(".editorconfig"),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = 2\nend_of_line = crlf\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("sub/.editorconfig"),
+            "# override\n[*.rs]\nindent_style = tab\ninsert_final_newline = false\n",
+        )
+        .unwrap();
+
+        let top = IndentOptions::from_editorconfig(root.join("top.rs")).unwrap();
+        let sub = IndentOptions::from_editorconfig(root.join("sub/lib.rs")).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(top.unit, IndentUnit::Spaces(2));
+        assert_eq!(top.line_ending, LineEnding::CrLf);
+        assert!(top.final_newline);
+        assert_eq!(sub.unit, IndentUnit::Tabs);
+        assert_eq!(sub.line_ending, LineEnding::CrLf);
+        assert!(!sub.final_newline);
+    }
+
+    #[test]
+    fn space_style_without_size() {
+        let mut settings = Settings::default();
+        settings.set("indent_style", "space");
+        assert_eq!(settings.finish().unit, IndentUnit::Spaces(4));
+
+        let mut settings = Settings::default();
+        settings.set("indent_style", "Space");
+        settings.set("tab_width", "8");
+        assert_eq!(settings.finish().unit, IndentUnit::Spaces(8));
+    }
+}
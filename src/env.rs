@@ -0,0 +1,63 @@
+use crate::{ColorChoice, IndentOptions, IndentUnit};
+
+impl IndentOptions {
+    /// Load the settings end users chose through environment variables
+    ///
+    /// # Explanation
+    ///
+    /// CLI tools can let their users pick an indentation style once for every tool built on this
+    /// crate, instead of each adding its own flags. `INDENTER_STYLE` sets the unit, as `tabs`,
+    /// `spaces` for four spaces or `spaces:N` for `N` spaces, and colors follow `NO_COLOR` and
+    /// `CLICOLOR_FORCE` as decided by `ColorChoice::Auto`. Variables that are unset or can't be
+    /// parsed leave the default in place. This is behind the `std` feature.
+    ///
+    /// ```rust
+    /// use indenter::{IndentOptions, IndentUnit};
+    ///
+    /// std::env::set_var("INDENTER_STYLE", "spaces:2");
+    /// let options = IndentOptions::from_env();
+    ///
+    /// assert_eq!(options.unit, IndentUnit::Spaces(2));
+    /// ```
+    pub fn from_env() -> Self {
+        let style = std::env::var("INDENTER_STYLE").ok();
+        Self::from_vars(style.as_deref(), ColorChoice::Auto.enabled())
+    }
+
+    fn from_vars(style: Option<&str>, color: bool) -> Self {
+        let mut options = Self::default();
+
+        if let Some(unit) = style.and_then(parse_style) {
+            options.unit = unit;
+        }
+        options.color = color;
+
+        options
+    }
+}
+
+/// Parses an `INDENTER_STYLE` value
+fn parse_style(style: &str) -> Option<IndentUnit> {
+    let style = style.trim().to_ascii_lowercase();
+    match style.split_once(':') {
+        Some(("spaces", width)) => width.trim().parse().ok().map(IndentUnit::Spaces),
+        None if style == "spaces" => Some(IndentUnit::Spaces(4)),
+        None if style == "tabs" => Some(IndentUnit::Tabs),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_and_color() {
+        let options = IndentOptions::from_vars(Some("Tabs"), false);
+        assert_eq!(options.unit, IndentUnit::Tabs);
+        assert!(!options.color);
+
+        let options = IndentOptions::from_vars(Some("spaces:three"), true);
+        assert_eq!(options, IndentOptions::default());
+    }
+}
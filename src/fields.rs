@@ -0,0 +1,163 @@
+use crate::{indented, DEFAULT_INDENTATION};
+use core::fmt::{self, Write};
+use tracing_core::field::{Field, Visit};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::FormatFields;
+
+/// A `tracing-subscriber` field formatter that indents multi-line field values
+///
+/// # Explanation
+///
+/// The default field formatter writes pretty-printed structs and error chains verbatim, so their
+/// continuation lines start at column zero and look like unrelated log lines. This formatter
+/// writes each value through `Indented` instead: the first line stays next to the field name and
+/// every following line is indented beneath it.
+///
+/// ```rust
+/// use indenter::IndentedFields;
+///
+/// let subscriber = tracing_subscriber::fmt()
+///     .fmt_fields(IndentedFields::new().with_str("  "))
+///     .finish();
+/// # drop(subscriber);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct IndentedFields {
+    indentation: &'static str,
+}
+
+impl IndentedFields {
+    /// Construct a field formatter with the default indentation, `DEFAULT_INDENTATION`
+    pub fn new() -> Self {
+        Self {
+            indentation: DEFAULT_INDENTATION,
+        }
+    }
+
+    /// Sets the string used to indent continuation lines of field values
+    pub fn with_str(mut self, indentation: &'static str) -> Self {
+        self.indentation = indentation;
+        self
+    }
+}
+
+impl Default for IndentedFields {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'writer> FormatFields<'writer> for IndentedFields {
+    fn format_fields<R: RecordFields>(&self, writer: Writer<'writer>, fields: R) -> fmt::Result {
+        let mut visitor = Visitor {
+            writer,
+            indentation: self.indentation,
+            is_empty: true,
+            result: Ok(()),
+        };
+
+        fields.record(&mut visitor);
+        visitor.result
+    }
+}
+
+struct Visitor<'writer> {
+    writer: Writer<'writer>,
+    indentation: &'static str,
+    is_empty: bool,
+    result: fmt::Result,
+}
+
+impl Visitor<'_> {
+    fn record(&mut self, name: &str, value: fmt::Arguments<'_>) {
+        if self.result.is_err() {
+            return;
+        }
+
+        self.result = self.write_field(name, value);
+    }
+
+    fn write_field(&mut self, name: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        if !self.is_empty {
+            self.writer.write_char(' ')?;
+        }
+        self.is_empty = false;
+
+        if name != "message" {
+            write!(self.writer, "{}=", name)?;
+        }
+
+        // The value continues the current line, only the lines after it are indented
+        let mut indented = indented(&mut self.writer).with_str(self.indentation);
+        indented.needs_indent = false;
+
+        indented.write_fmt(value)
+    }
+}
+
+impl Visit for Visitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field.name(), format_args!("{}", value));
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.record(field.name(), format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field.name(), format_args!("{:?}", value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture(f: impl FnOnce()) -> String {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .fmt_fields(IndentedFields::new().with_str("  "))
+            .without_time()
+            .with_target(false)
+            .with_level(false)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, f);
+
+        let bytes = buffer.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn single_line_fields() {
+        let output = capture(|| tracing::info!(answer = 42, name = "indenter", "hello"));
+
+        assert_eq!(output, "hello answer=42 name=indenter\n");
+    }
+
+    #[test]
+    fn multi_line_value() {
+        let output = capture(|| tracing::info!(error = "first\nsecond", "failed"));
+
+        assert_eq!(output, "failed error=first\n  second\n");
+    }
+}
@@ -0,0 +1,185 @@
+use core::fmt::{self, Write};
+
+/// Helper struct for quoting `format=flowed` text one level deeper, as in a reply to an email
+///
+/// # Explanation
+///
+/// In `format=flowed` text (RFC 3676) a line ending in a space is a soft break, which mail clients
+/// join with the next line and re-wrap to the width of the window, and quoted lines start with
+/// one `>` per level of quoting. Prefixing every line with `"> "` by hand breaks this: blank lines
+/// become `"> "`, which reads as a soft break, and text that was already quoted becomes `"> >"`,
+/// which is one level deeper than intended. This writes every line with one more `>` than it had,
+/// keeps soft and hard breaks as they are, and writes blank lines as `>` alone. The signature
+/// separator `"-- "` is kept as it is.
+///
+/// With `reflow`, the lines joined by soft breaks are wrapped again to fit `width` columns with
+/// the quote markers, ending every line but the last of a paragraph with a soft break. Lines of
+/// input may end in `\r\n`, and are written with `\n`.
+///
+/// ```rust
+/// use indenter::quote_flowed;
+///
+/// let reply = "Sounds good to \nme.\n\n> When should \n> we meet?";
+///
+/// assert_eq!(
+///     quote_flowed(reply).to_string(),
+///     "> Sounds good to \n> me.\n>\n>> When should \n>> we meet?"
+/// );
+/// assert_eq!(
+///     quote_flowed(reply).reflow(12).to_string(),
+///     "> Sounds \n> good to \n> me.\n>\n>> When \n>> should \n>> we meet?"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FlowedQuote<'a> {
+    text: &'a str,
+    width: Option<usize>,
+}
+
+/// Create a `FlowedQuote` that quotes `text` one level deeper
+pub fn quote_flowed(text: &str) -> FlowedQuote<'_> {
+    FlowedQuote { text, width: None }
+}
+
+impl FlowedQuote<'_> {
+    /// Wrap the paragraphs formed by soft breaks again, to lines of at most `width` characters
+    /// including the quote markers and the soft break
+    ///
+    /// Words wider than the lines are written on a line of their own.
+    pub fn reflow(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+}
+
+/// A line of `format=flowed` text
+struct FlowedLine<'a> {
+    depth: usize,
+    /// The text of the line without quote markers and space stuffing
+    content: &'a str,
+}
+
+impl<'a> FlowedLine<'a> {
+    fn parse(line: &'a str) -> Self {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let content = line.trim_start_matches('>');
+        let depth = line.len() - content.len();
+        let content = content.strip_prefix(' ').unwrap_or(content);
+
+        Self { depth, content }
+    }
+
+    fn is_signature(&self) -> bool {
+        self.content == "-- "
+    }
+
+    /// Whether the line ends in a soft break
+    fn is_flowed(&self) -> bool {
+        self.content.ends_with(' ') && !self.is_signature()
+    }
+}
+
+/// Writes `depth` quote markers, and the space that separates them from `content` if there is
+/// any
+fn write_quoted(f: &mut fmt::Formatter<'_>, depth: usize, content: &str) -> fmt::Result {
+    for _ in 0..depth {
+        f.write_char('>')?;
+    }
+
+    if content.is_empty() {
+        Ok(())
+    } else {
+        write!(f, " {}", content)
+    }
+}
+
+impl fmt::Display for FlowedQuote<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.text.strip_suffix('\n').unwrap_or(self.text);
+        let lines = text.split('\n').map(FlowedLine::parse);
+
+        let width = match self.width {
+            Some(width) => width,
+            None => {
+                for (i, line) in lines.enumerate() {
+                    if i > 0 {
+                        f.write_char('\n')?;
+                    }
+                    write_quoted(f, line.depth + 1, line.content)?;
+                }
+
+                return Ok(());
+            }
+        };
+
+        // The depth of the paragraph being filled, and the column on its current line
+        let mut paragraph: Option<(usize, usize)> = None;
+        let mut started = false;
+        for line in lines {
+            let depth = line.depth + 1;
+            if line.is_signature() || paragraph.is_some_and(|(open, _)| open != depth) {
+                paragraph = None;
+            }
+
+            let mut column = match paragraph {
+                Some((_, column)) => column,
+                None => {
+                    if started {
+                        f.write_char('\n')?;
+                    }
+                    started = true;
+
+                    if line.is_signature() {
+                        write_quoted(f, depth, line.content)?;
+                        continue;
+                    }
+                    write_quoted(f, depth, "")?;
+                    depth
+                }
+            };
+
+            for word in line.content.split(' ').filter(|word| !word.is_empty()) {
+                let len = word.chars().count();
+
+                // Leave room for the space before the word and the soft break after it
+                if column > depth && column + len + 2 > width {
+                    f.write_str(" \n")?;
+                    write_quoted(f, depth, "")?;
+                    column = depth;
+                }
+
+                write!(f, " {}", word)?;
+                column += len + 1;
+            }
+
+            paragraph = if line.is_flowed() {
+                Some((depth, column))
+            } else {
+                None
+            };
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn blank_lines_and_signatures() {
+        let text = "> quoted\r\n\r\n-- \r\nAlice\r\n";
+
+        assert_eq!(
+            quote_flowed(text).to_string(),
+            ">> quoted\n>\n> -- \n> Alice"
+        );
+        assert_eq!(
+            quote_flowed(text).reflow(20).to_string(),
+            ">> quoted\n>\n> -- \n> Alice"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::Format;
+use core::cell::Cell;
+
+/// Numbering shared between the indenters of a group, such as the elements of an error chain
+///
+/// # Explanation
+///
+/// Error chains are usually rendered with a fresh `Indented` per element, so no element knows the
+/// gutter width its siblings need. A `GutterState` is borrowed by every element instead. Indices
+/// are first reserved for the whole group, after which each element takes the next index along
+/// with a `Format::NumberedMax` sized for the largest index reserved.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, GutterState};
+///
+/// let chain = ["outer", "middle", "inner"];
+/// let gutter = GutterState::new().with_start(9);
+/// for _ in &chain {
+///     gutter.reserve();
+/// }
+///
+/// let mut output = String::new();
+/// for error in &chain {
+///     writeln!(indented(&mut output).with_format(gutter.numbered()), "{}", error)?;
+/// }
+///
+/// assert_eq!(output, " 9: outer\n10: middle\n11: inner\n");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GutterState {
+    start: usize,
+    next: Cell<usize>,
+    reserved: Cell<Option<usize>>,
+}
+
+impl GutterState {
+    /// Construct a state whose indices start from 0
+    pub const fn new() -> Self {
+        Self {
+            start: 0,
+            next: Cell::new(0),
+            reserved: Cell::new(None),
+        }
+    }
+
+    /// Sets the index of the first element
+    pub fn with_start(self, start: usize) -> Self {
+        Self {
+            start,
+            next: Cell::new(start),
+            reserved: Cell::new(None),
+        }
+    }
+
+    /// Reserve room in the gutter for one more element and return its index
+    pub fn reserve(&self) -> usize {
+        let ind = self.reserved.get().map_or(self.start, |max| max + 1);
+        self.reserved.set(Some(ind));
+        ind
+    }
+
+    /// Returns the largest index reserved so far, if any
+    pub fn max(&self) -> Option<usize> {
+        self.reserved.get()
+    }
+
+    /// Take the index of the next element to be written
+    ///
+    /// Taking an index that was not reserved reserves it, but elements already written keep the
+    /// gutter width they were written with.
+    pub fn next_index(&self) -> usize {
+        let ind = self.next.get();
+        self.next.set(ind + 1);
+
+        if !matches!(self.reserved.get(), Some(max) if max >= ind) {
+            self.reserved.set(Some(ind));
+        }
+
+        ind
+    }
+
+    /// Take the index of the next element and return a `Format::NumberedMax` for it with a gutter
+    /// sized for the whole group
+    pub fn numbered<'a>(&self) -> Format<'a> {
+        let ind = self.next_index();
+
+        Format::NumberedMax {
+            ind,
+            max: self.reserved.get().unwrap_or(ind),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use crate::indented;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn unreserved_indices_grow_the_gutter() {
+        let gutter = GutterState::new();
+        gutter.reserve();
+
+        let mut output = String::new();
+        for _ in 0..11 {
+            write!(indented(&mut output).with_format(gutter.numbered()), "x").unwrap();
+        }
+
+        assert_eq!(output, "0: x1: x2: x3: x4: x5: x6: x7: x8: x9: x10: x");
+        assert_eq!(gutter.max(), Some(10));
+    }
+}
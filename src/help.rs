@@ -0,0 +1,177 @@
+use crate::{indented, wrap, Format, Indent, Indentation};
+use core::fmt::{self, Write};
+use core::mem;
+
+/// A writer for command line help and usage text
+///
+/// # Explanation
+///
+/// Help output is made of sections, each with a header followed by indented entries. Option
+/// entries have their names in a column on the left and descriptions in an aligned column on the
+/// right, which wrap to the width of the terminal with a hanging indent so continuation lines stay
+/// in the description column. A name too wide for its column gets its description on the next
+/// line instead.
+///
+/// ```rust
+/// use indenter::HelpWriter;
+///
+/// let mut output = String::new();
+/// let mut help = HelpWriter::new(&mut output, 40).with_name_width(10);
+/// help.usage("app [OPTIONS] <FILE>")?;
+/// help.section("Options:")?;
+/// help.option("-q", "Do not print anything but errors to the terminal")?;
+/// help.option("--color <WHEN>", "When to color output")?;
+///
+/// assert_eq!(
+///     output,
+///     "\
+/// Usage: app [OPTIONS] <FILE>
+///
+/// Options:
+///   -q          Do not print anything but
+///               errors to the terminal
+///   --color <WHEN>
+///               When to color output
+/// "
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct HelpWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    width: usize,
+    indent: usize,
+    name_width: usize,
+    started: bool,
+}
+
+impl<'a> HelpWriter<'a> {
+    /// Construct a writer that wraps its output to `width` columns
+    pub fn new(inner: &'a mut dyn fmt::Write, width: usize) -> Self {
+        Self {
+            inner,
+            width,
+            indent: 2,
+            name_width: 24,
+            started: false,
+        }
+    }
+
+    /// Construct a writer that wraps to the width given by the `COLUMNS` environment variable, or
+    /// 80 columns if it is not set
+    #[cfg(feature = "std")]
+    pub fn from_env(inner: &'a mut dyn fmt::Write) -> Self {
+        let width = std::env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.trim().parse().ok())
+            .unwrap_or(80);
+
+        Self::new(inner, width)
+    }
+
+    /// Sets the width of the option name column
+    pub fn with_name_width(mut self, name_width: usize) -> Self {
+        self.name_width = name_width;
+        self
+    }
+
+    /// Sets the indentation of entries within a section
+    pub fn with_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Write a `Usage:` line, wrapped with continuation lines under the start of `usage`
+    pub fn usage(&mut self, usage: &str) -> fmt::Result {
+        self.begin_block()?;
+        self.inner.write_str("Usage: ")?;
+        self.hanging(7, usage)?;
+        self.inner.write_char('\n')
+    }
+
+    /// Write a section header, separated from previous output by a blank line
+    pub fn section(&mut self, header: impl fmt::Display) -> fmt::Result {
+        self.begin_block()?;
+        writeln!(self.inner, "{}", header)
+    }
+
+    /// Write a paragraph of text wrapped at the indentation of entries
+    pub fn paragraph(&mut self, text: &str) -> fmt::Result {
+        let indent = Indentation::new(Indent(self.indent), " ");
+        write!(self.inner, "{}", indent)?;
+        self.hanging(self.indent, text)?;
+        self.inner.write_char('\n')
+    }
+
+    /// Write an option entry with its description wrapped in the description column
+    pub fn option(&mut self, name: &str, description: &str) -> fmt::Result {
+        let column = self.indent + self.name_width + 2;
+        let len = name.chars().count();
+
+        write!(
+            self.inner,
+            "{}{}",
+            Indentation::new(Indent(self.indent), " "),
+            name
+        )?;
+
+        if len <= self.name_width {
+            let padding = column - self.indent - len;
+            write!(self.inner, "{}", Indentation::new(Indent(padding), " "))?;
+        } else {
+            write!(self.inner, "\n{}", Indentation::new(Indent(column), " "))?;
+        }
+
+        self.hanging(column, description)?;
+        self.inner.write_char('\n')
+    }
+
+    fn begin_block(&mut self) -> fmt::Result {
+        if mem::replace(&mut self.started, true) {
+            self.inner.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes `text` wrapped to the remaining width, starting at `column` on the current line and
+    /// indenting continuation lines to the same column
+    fn hanging(&mut self, column: usize, text: &str) -> fmt::Result {
+        let width = self.width.saturating_sub(column).max(1);
+        let mut inserter =
+            |_, f: &mut dyn Write| write!(f, "{}", Indentation::new(Indent(column), " "));
+        let mut indented = indented(&mut *self.inner).with_format(Format::Custom {
+            inserter: &mut inserter,
+        });
+        indented.needs_indent = false;
+
+        write!(indented, "{}", wrap(text, width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn narrow_terminal() {
+        let mut output = String::new();
+        let mut help = HelpWriter::new(&mut output, 8).with_name_width(2);
+        help.option("-v", "be very loud").unwrap();
+
+        assert_eq!(output, "  -v  be\n      very\n      loud\n");
+    }
+
+    #[test]
+    fn paragraphs_in_sections() {
+        let mut output = String::new();
+        let mut help = HelpWriter::new(&mut output, 12).with_indent(4);
+        help.section("About:").unwrap();
+        help.paragraph("formats help text").unwrap();
+
+        assert_eq!(output, "About:\n    formats\n    help\n    text\n");
+    }
+}
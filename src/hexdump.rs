@@ -0,0 +1,139 @@
+use crate::write_padded;
+use core::fmt;
+
+/// The number of bytes shown on each line of a `HexDump`
+const ROW: usize = 16;
+
+/// A writer that renders bytes as a classic hex dump
+///
+/// # Explanation
+///
+/// Every line shows the offset of its first byte, the bytes themselves as hex in two groups of
+/// eight, and the same bytes as ASCII with anything unprintable shown as a `.`, like
+/// `hexdump -C`. Bytes are buffered until a line is full, so a dump can be written in pieces as
+/// the data arrives, and `finish` writes the last, partial line. The dump is written as text to
+/// the inner writer, which can be an `Indented` to nest it in a larger report.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, HexDump};
+///
+/// let mut output = String::new();
+/// writeln!(output, "packet:")?;
+/// let mut dump = HexDump::new(indented(&mut output).with_str("  "));
+/// dump.write(b"GET / HTTP/1.1\r\n")?;
+/// dump.write(b"Host: a\r\n")?;
+/// dump.finish()?;
+///
+/// assert_eq!(
+///     output,
+///     concat!(
+///         "packet:\n",
+///         "  00000000  47 45 54 20 2f 20 48 54  54 50 2f 31 2e 31 0d 0a  |GET / HTTP/1.1..|\n",
+///         "  00000010  48 6f 73 74 3a 20 61 0d  0a                       |Host: a..|\n",
+///     )
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct HexDump<W> {
+    inner: W,
+    offset: usize,
+    row: [u8; ROW],
+    len: usize,
+}
+
+impl<W: fmt::Write> HexDump<W> {
+    /// Construct a dump whose offsets start at 0
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            offset: 0,
+            row: [0; ROW],
+            len: 0,
+        }
+    }
+
+    /// Sets the offset shown for the first byte, such as the address the data was read from
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Add `bytes` to the dump, writing every line they complete
+    pub fn write(&mut self, mut bytes: &[u8]) -> fmt::Result {
+        while !bytes.is_empty() {
+            let take = (ROW - self.len).min(bytes.len());
+            self.row[self.len..self.len + take].copy_from_slice(&bytes[..take]);
+            self.len += take;
+            bytes = &bytes[take..];
+
+            if self.len == ROW {
+                self.write_row()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the last, partial line of the dump and return the inner writer
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        if self.len > 0 {
+            self.write_row()?;
+        }
+
+        Ok(self.inner)
+    }
+
+    fn write_row(&mut self) -> fmt::Result {
+        write_padded(self.offset, 16, 8, b'0', "  ", &mut self.inner)?;
+
+        for i in 0..ROW {
+            if i == ROW / 2 {
+                self.inner.write_char(' ')?;
+            }
+
+            match self.row[..self.len].get(i) {
+                Some(&byte) => write_padded(byte.into(), 16, 2, b'0', " ", &mut self.inner)?,
+                None => self.inner.write_str("   ")?,
+            }
+        }
+
+        self.inner.write_str(" |")?;
+        for &byte in &self.row[..self.len] {
+            let c = if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            };
+            self.inner.write_char(c)?;
+        }
+        self.inner.write_str("|\n")?;
+
+        self.offset += self.len;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn pieces_fill_rows() {
+        let mut dump = HexDump::new(String::new()).with_offset(0xfff0);
+        for byte in 0..20u8 {
+            dump.write(&[byte + b'a']).unwrap();
+        }
+        let output = dump.finish().unwrap();
+
+        let mut lines = output.lines();
+        assert!(lines.next().unwrap().starts_with("0000fff0  61 62"));
+        assert!(lines.next().unwrap().ends_with("|qrst|"));
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -0,0 +1,141 @@
+use core::fmt;
+
+/// A writer that escapes text for embedding in HTML
+///
+/// # Explanation
+///
+/// Indented reports published to a web page need their markup characters escaped, and their
+/// indentation preserved. Inside a `<pre>` element escaping is all it takes. Elsewhere, `nbsp`
+/// also turns the leading spaces of every line into `&nbsp;` and ends lines with `<br>`, so the
+/// browser does not collapse the indentation.
+///
+/// Put the writer in front of an `Indented`, so the text is escaped before the indentation is
+/// added, and use an `HtmlGutter` to wrap the indentation of every line in a `<span>` with a class
+/// for styling it.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, HtmlGutter, HtmlWriter};
+///
+/// let gutter = HtmlGutter::new("gutter");
+/// let mut span = |text: &str, f: &mut dyn Write| gutter.write(text, f);
+///
+/// let mut output = String::from("<pre>");
+/// let mut indented = indented(&mut output).with_str("| ").stylize_gutter(&mut span);
+/// write!(HtmlWriter::new(&mut indented), "Vec<u8>\n&str")?;
+/// output.push_str("</pre>");
+///
+/// assert_eq!(
+///     output,
+///     "<pre><span class=\"gutter\">| </span>Vec&lt;u8&gt;\n\
+///      <span class=\"gutter\">| </span>&amp;str</pre>"
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct HtmlWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    nbsp: bool,
+    line_start: bool,
+}
+
+impl<'a, W: ?Sized> HtmlWriter<'a, W> {
+    /// Construct a writer that escapes text written to `inner`
+    pub fn new(inner: &'a mut W) -> Self {
+        Self {
+            inner,
+            nbsp: false,
+            line_start: true,
+        }
+    }
+
+    /// Keep leading spaces and line breaks visible outside of a `<pre>` element
+    pub fn nbsp(mut self) -> Self {
+        self.nbsp = true;
+        self
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for HtmlWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut plain = 0;
+
+        for (i, c) in s.char_indices() {
+            let entity = match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                '\n' if self.nbsp => "<br>\n",
+                ' ' if self.nbsp && self.line_start => "&nbsp;",
+                _ => {
+                    self.line_start = false;
+                    continue;
+                }
+            };
+
+            self.line_start = c == '\n' || (c == ' ' && self.line_start);
+            self.inner.write_str(&s[plain..i])?;
+            self.inner.write_str(entity)?;
+            plain = i + c.len_utf8();
+        }
+
+        self.inner.write_str(&s[plain..])
+    }
+}
+
+/// Writes the indentation of a line wrapped in a `<span>` with a class, for
+/// `Indented::stylize_gutter`
+///
+/// The indentation is escaped, and with `nbsp` its spaces are written as `&nbsp;`.
+#[derive(Debug, Clone, Copy)]
+pub struct HtmlGutter<'a> {
+    class: &'a str,
+    nbsp: bool,
+}
+
+impl<'a> HtmlGutter<'a> {
+    /// Construct a gutter wrapped in a span with the given class
+    pub fn new(class: &'a str) -> Self {
+        Self { class, nbsp: false }
+    }
+
+    /// Write spaces in the indentation as `&nbsp;`, for use outside of a `<pre>` element
+    pub fn nbsp(mut self) -> Self {
+        self.nbsp = true;
+        self
+    }
+
+    /// Write `gutter` wrapped in a span
+    pub fn write(&self, gutter: &str, f: &mut dyn fmt::Write) -> fmt::Result {
+        write!(f, "<span class=\"{}\">", self.class)?;
+
+        let mut escape = HtmlWriter {
+            inner: f,
+            nbsp: self.nbsp,
+            line_start: true,
+        };
+        fmt::Write::write_str(&mut escape, gutter)?;
+
+        f.write_str("</span>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn nbsp_only_replaces_leading_spaces() {
+        let mut output = String::new();
+        let mut html = HtmlWriter::new(&mut output).nbsp();
+        html.write_str("a  b\n  ").unwrap();
+        html.write_str(" c d").unwrap();
+
+        assert_eq!(output, "a  b<br>\n&nbsp;&nbsp;&nbsp;c d");
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{indented, Format, LineAction};
+use core::fmt;
+
+/// An indenter that owns the writer it writes to
+///
+/// # Explanation
+///
+/// `Indented` borrows its writer, so it cannot be stored next to that writer in a struct. This
+/// type owns the writer instead, which lets report builders keep their indented buffer as a field
+/// and take the writer back out with `into_inner` once they are done. Line state is kept between
+/// writes, exactly like a single long lived `Indented`.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::IndentedOwned;
+///
+/// struct Report {
+///     body: IndentedOwned<'static, String>,
+/// }
+///
+/// let mut report = Report {
+///     body: IndentedOwned::new(String::new()).with_str("  - "),
+/// };
+/// writeln!(report.body, "first")?;
+/// writeln!(report.body, "second")?;
+///
+/// assert_eq!(report.body.into_inner(), "  - first\n  - second\n");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct IndentedOwned<'a, W> {
+    inner: W,
+    format: Format<'a>,
+    skip_empty: bool,
+    needs_indent: bool,
+    line: usize,
+    action: LineAction,
+}
+
+impl<'a, W> IndentedOwned<'a, W> {
+    /// Construct an indenter that owns `inner`, indenting by `DEFAULT_INDENTATION` like `indented`
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            format: Format::DEFAULT,
+            skip_empty: true,
+            needs_indent: true,
+            line: 0,
+            action: LineAction::Keep,
+        }
+    }
+
+    /// Sets the format to `Format::Numbered` with the provided index
+    pub fn ind(self, ind: usize) -> Self {
+        self.with_format(Format::Numbered { ind })
+    }
+
+    /// Sets the format to `Format::Uniform` with the provided static string
+    pub fn with_str(self, indentation: &'static str) -> Self {
+        self.with_format(Format::Uniform { indentation })
+    }
+
+    /// Construct an indenter with a user defined format
+    pub fn with_format(mut self, format: Format<'a>) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets whether empty lines are left without indentation, like `Indented::skip_empty`
+    pub fn skip_empty(mut self, skip: bool) -> Self {
+        self.skip_empty = skip;
+        self
+    }
+
+    /// Returns a reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer
+    ///
+    /// Anything written to it directly ends up in front of the indentation of the line being
+    /// written, if that line has not been indented yet.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this indenter, returning the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for IndentedOwned<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut indented = indented(&mut self.inner)
+            .with_format(self.format.reborrow())
+            .skip_empty(self.skip_empty);
+        indented.needs_indent = self.needs_indent;
+        indented.line = self.line;
+        indented.action = self.action;
+
+        let result = indented.write_str(s);
+        self.needs_indent = indented.needs_indent;
+        self.line = indented.line;
+        self.action = indented.action;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn line_numbers_persist() {
+        let mut numbered = IndentedOwned::new(String::new()).ind(3);
+        numbered.write_str("a\n").unwrap();
+        numbered.write_str("b").unwrap();
+        numbered.get_mut().push('!');
+
+        assert_eq!(numbered.get_ref(), "   3: a\n      b!");
+    }
+}
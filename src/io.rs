@@ -0,0 +1,449 @@
+use crate::Format;
+use std::io;
+
+/// An indenter for `io::Write`, for indenting bytes such as the output of another process
+///
+/// # Explanation
+///
+/// `Indented` works on text through `fmt::Write`, while pipes, files and sockets are written
+/// through `io::Write`, where a write may accept fewer bytes than it was given. This adapter keeps
+/// track of where it is in the indentation of the current line, so when the inner writer takes
+/// only part of it, the rest is written first on the next call. The count returned from `write`
+/// only ever covers bytes of the caller, never the indentation, so `write_all` and `io::copy`
+/// work as usual. As with `Indented`, empty lines are not indented and the indentation of a line
+/// is only written once the first byte of the line is.
+///
+/// Errors from the inner writer, such as `WouldBlock` from a non-blocking socket, leave the
+/// adapter where it stopped, even in the middle of the indentation, and a write of the same bytes
+/// again continues from there. When some bytes were accepted before the error, their count is
+/// returned instead, and the error is left for the next call to run into.
+///
+/// A UTF-8 character split across writes is held back until the rest of it is written, so the
+/// inner writer is never handed part of a character, and adapters that look at the text, such as
+/// a `fmt::Write` bridge, always see whole characters. `flush` writes a held back start of a
+/// character as it is.
+///
+/// ```rust
+/// use std::io::Write;
+/// use indenter::indented_io;
+///
+/// let mut output = Vec::new();
+/// write!(indented_io(&mut output).with_str("> "), "line 1\n\nline 2")?;
+///
+/// assert_eq!(output, b"> line 1\n\n> line 2");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct IoIndented<'a, W: ?Sized> {
+    inner: &'a mut W,
+    format: Format<'a>,
+    /// The indentation of the current line, once it is started
+    gutter: String,
+    state: State,
+    line: usize,
+    skip_lines: usize,
+    skip_empty: bool,
+    line_buffered: bool,
+    /// Whether a line was finished since the inner writer was last flushed
+    flush_pending: bool,
+    held: Held,
+}
+
+/// Where an `IoIndented` is within the current line
+///
+/// Each state is a point at which a write can stop, because the inner writer accepted only part
+/// of its input or returned an error such as `WouldBlock`, and continue from on the next call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// At the start of a line, before its indentation is rendered
+    LineStart,
+    /// Writing the indentation of the line, of which `written` bytes were accepted
+    Gutter { written: usize },
+    /// Writing the text of the line
+    Text,
+}
+
+/// The start of a UTF-8 character split across writes, held back until the rest of it arrives
+#[derive(Debug, Clone, Copy, Default)]
+struct Held {
+    bytes: [u8; 4],
+    len: usize,
+    /// How much of `bytes` the inner writer has accepted, once the character is complete
+    written: usize,
+}
+
+/// Helper function for creating an indenter for an `io::Write`
+pub fn indented_io<W: ?Sized>(inner: &mut W) -> IoIndented<'_, W> {
+    IoIndented {
+        inner,
+        format: Format::DEFAULT,
+        gutter: String::new(),
+        state: State::LineStart,
+        line: 0,
+        skip_lines: 0,
+        skip_empty: true,
+        line_buffered: false,
+        flush_pending: false,
+        held: Held::default(),
+    }
+}
+
+impl<'a, W: ?Sized> IoIndented<'a, W> {
+    /// Sets the format to `Format::Numbered` with the provided index
+    pub fn ind(self, ind: usize) -> Self {
+        self.with_format(Format::Numbered { ind })
+    }
+
+    /// Sets the format to `Format::Uniform` with the provided static string
+    pub fn with_str(self, indentation: &'static str) -> Self {
+        self.with_format(Format::Uniform { indentation })
+    }
+
+    /// Sets the format the indentation of every line is rendered with
+    pub fn with_format(mut self, format: Format<'a>) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the number of lines at the start of the output that are written without indentation
+    pub fn skip_lines(mut self, lines: usize) -> Self {
+        self.skip_lines = lines;
+        self
+    }
+
+    /// Sets whether blank lines are left empty instead of being indented
+    pub fn skip_empty(mut self, skip: bool) -> Self {
+        self.skip_empty = skip;
+        self
+    }
+
+    /// Flush the inner writer after every newline
+    ///
+    /// This keeps output that is prefixed as it arrives, such as the stdout and stderr of a
+    /// subprocess, readable when the inner writer buffers, for example `io::stdout`. Without it
+    /// the inner writer is only flushed by `flush`. A flush that fails after some bytes were
+    /// accepted is attempted again on the next call.
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    /// use indenter::indented_io;
+    ///
+    /// let stdout = std::io::stdout();
+    /// let mut stdout = stdout.lock();
+    /// let mut prefixed = indented_io(&mut stdout).with_str("[build] ").line_buffered(true);
+    /// writeln!(prefixed, "Compiling indenter")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn line_buffered(mut self, line_buffered: bool) -> Self {
+        self.line_buffered = line_buffered;
+        self
+    }
+}
+
+impl<W: io::Write + ?Sized> IoIndented<'_, W> {
+    /// Render the indentation of the current line, to be written by `write_gutter`
+    fn start_gutter(&mut self) -> io::Result<()> {
+        self.gutter.clear();
+        self.format
+            .render_gutter(self.line, &mut self.gutter)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "formatter error"))?;
+        self.state = State::Gutter { written: 0 };
+        Ok(())
+    }
+
+    /// Flush the inner writer if a line was finished since it was last flushed
+    fn flush_line(&mut self) -> io::Result<()> {
+        if self.flush_pending {
+            self.inner.flush()?;
+            self.flush_pending = false;
+        }
+
+        Ok(())
+    }
+
+    /// Write what is left of the indentation of the current line
+    fn write_gutter(&mut self) -> io::Result<()> {
+        while let State::Gutter { written } = self.state {
+            if written == self.gutter.len() {
+                self.state = State::Text;
+                break;
+            }
+
+            match self.inner.write(&self.gutter.as_bytes()[written..])? {
+                0 => return Err(io::ErrorKind::WriteZero.into()),
+                n => {
+                    self.state = State::Gutter {
+                        written: written + n,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the held back character, complete or not
+    fn write_held(&mut self) -> io::Result<()> {
+        let held = &mut self.held;
+        while held.written < held.len {
+            match self.inner.write(&held.bytes[held.written..held.len])? {
+                0 => return Err(io::ErrorKind::WriteZero.into()),
+                n => held.written += n,
+            }
+        }
+
+        *held = Held::default();
+        Ok(())
+    }
+}
+
+/// Returns the length of the UTF-8 character that starts with `byte`
+fn char_len(byte: u8) -> usize {
+    match byte {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => 1,
+    }
+}
+
+/// Returns the length of the incomplete UTF-8 character at the end of `bytes`, if there is one
+fn incomplete_tail(bytes: &[u8]) -> usize {
+    for len in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - len];
+        if byte & 0xc0 != 0x80 {
+            return if char_len(byte) > len { len } else { 0 };
+        }
+    }
+
+    0
+}
+
+/// Returns the number of bytes accepted before `error`, or `error` if there were none
+fn partial(consumed: usize, error: io::Error) -> io::Result<usize> {
+    if consumed > 0 {
+        Ok(consumed)
+    } else {
+        Err(error)
+    }
+}
+
+impl<W: io::Write + ?Sized> io::Write for IoIndented<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut consumed = 0;
+        while consumed < buf.len() {
+            if let Err(e) = self.flush_line() {
+                return partial(consumed, e);
+            }
+
+            let result = match self.state {
+                State::LineStart
+                    if self.line < self.skip_lines
+                        || (self.skip_empty && buf[consumed] == b'\n') =>
+                {
+                    self.state = State::Text;
+                    Ok(())
+                }
+                State::LineStart => self.start_gutter(),
+                State::Gutter { .. } => self.write_gutter(),
+                State::Text => Ok(()),
+            };
+            if let Err(e) = result {
+                return partial(consumed, e);
+            }
+            if self.state != State::Text {
+                continue;
+            }
+
+            if self.held.len > 0 {
+                let held = &mut self.held;
+                while held.written == 0
+                    && held.len < char_len(held.bytes[0])
+                    && buf.get(consumed).is_some_and(|&b| b & 0xc0 == 0x80)
+                {
+                    held.bytes[held.len] = buf[consumed];
+                    held.len += 1;
+                    consumed += 1;
+                }
+
+                if consumed == buf.len() && held.len < char_len(held.bytes[0]) {
+                    break;
+                }
+                if let Err(e) = self.write_held() {
+                    return partial(consumed, e);
+                }
+                continue;
+            }
+
+            let end = match buf[consumed..].iter().position(|&b| b == b'\n') {
+                Some(newline) => consumed + newline + 1,
+                None => buf.len(),
+            };
+            let tail = incomplete_tail(&buf[consumed..end]);
+            let written = match &buf[consumed..end - tail] {
+                [] => 0,
+                chunk => match self.inner.write(chunk) {
+                    Ok(written) => written,
+                    Err(e) => return partial(consumed, e),
+                },
+            };
+
+            if written == end - tail - consumed && tail > 0 {
+                self.held.bytes[..tail].copy_from_slice(&buf[end - tail..end]);
+                self.held.len = tail;
+                consumed = end;
+                continue;
+            }
+
+            consumed += written;
+            if written > 0 && buf[consumed - 1] == b'\n' {
+                self.state = State::LineStart;
+                self.flush_pending = self.line_buffered;
+                self.line += 1;
+            }
+
+            if consumed < end {
+                break;
+            }
+        }
+
+        match self.flush_line() {
+            Ok(()) => Ok(consumed),
+            Err(e) => partial(consumed, e),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_gutter()?;
+        self.write_held()?;
+        self.flush_pending = false;
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// A writer that accepts a single byte per call
+    struct OneByte(Vec<u8>);
+
+    impl Write for OneByte {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.extend(buf.first());
+            Ok(buf.len().min(1))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn counts_only_caller_bytes() {
+        let mut inner = OneByte(Vec::new());
+        let mut indented = indented_io(&mut inner).ind(1);
+
+        assert_eq!(indented.write(b"ab").unwrap(), 1);
+        assert_eq!(indented.write(b"b\n").unwrap(), 1);
+        indented.write_all(b"\n\nc\nd").unwrap();
+        drop(indented);
+
+        assert_eq!(inner.0, b"   1: ab\n\n      c\n      d");
+    }
+
+    #[test]
+    fn flush_after_newlines() {
+        struct Flushes(Vec<String>);
+
+        impl Write for Flushes {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let last = self.0.last_mut().unwrap();
+                last.push_str(std::str::from_utf8(buf).unwrap());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.0.push(String::new());
+                Ok(())
+            }
+        }
+
+        let mut inner = Flushes(vec![String::new()]);
+        let mut indented = indented_io(&mut inner).with_str("| ").line_buffered(true);
+        indented.write_all(b"a\nb").unwrap();
+        indented.write_all(b"c\n\nd\n").unwrap();
+        drop(indented);
+
+        assert_eq!(inner.0, ["| a\n", "| bc\n", "\n", "| d\n", ""]);
+    }
+
+    #[test]
+    fn split_characters_are_held_back() {
+        struct Chunks(Vec<String>);
+
+        impl Write for Chunks {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.push(String::from_utf8(buf.to_vec()).unwrap());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut inner = Chunks(Vec::new());
+        let mut indented = indented_io(&mut inner).with_str("> ");
+        for byte in "é—\n😀".bytes() {
+            indented.write_all(&[byte]).unwrap();
+        }
+        drop(indented);
+
+        assert_eq!(inner.0, ["> ", "é", "—", "\n", "> ", "😀"]);
+    }
+
+    #[test]
+    fn resume_after_would_block() {
+        /// A writer that fails every other call with `WouldBlock`, and accepts 3 bytes otherwise
+        struct Blocking(Vec<u8>, bool);
+
+        impl Blocking {
+            fn block(&mut self) -> io::Result<()> {
+                self.1 = !self.1;
+                if self.1 {
+                    Err(io::ErrorKind::WouldBlock.into())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        impl Write for Blocking {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.block()?;
+                let len = buf.len().min(3);
+                self.0.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.block()
+            }
+        }
+
+        let mut inner = Blocking(Vec::new(), false);
+        let mut indented = indented_io(&mut inner).ind(12).line_buffered(true);
+        let mut buf = &b"first\n\nsecond\nthird"[..];
+        while !buf.is_empty() {
+            match indented.write(buf) {
+                Ok(written) => buf = &buf[written..],
+                Err(e) => assert_eq!(e.kind(), io::ErrorKind::WouldBlock),
+            }
+        }
+        drop(indented);
+
+        assert_eq!(inner.0, b"  12: first\n\n      second\n      third");
+    }
+}
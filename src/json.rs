@@ -0,0 +1,290 @@
+use crate::{Indent, Indentation};
+use core::fmt::{self, Write};
+
+/// A writer for emitting pretty printed JSON without `serde` or allocation
+///
+/// # Explanation
+///
+/// Objects and arrays are written as closure scopes, so the nesting of the output follows the
+/// nesting of the code and every scope is closed once its closure returns successfully. An error
+/// returned by a closure is passed on without closing its scope, since the output is incomplete
+/// at that point anyway. Inside a scope the writer keeps track of whether a separating comma is
+/// needed and indents every entry by its depth. Object entries are started with `field`, which
+/// writes the quoted key and returns the writer for the value.
+///
+/// ```rust
+/// use indenter::JsonWriter;
+///
+/// let mut output = String::new();
+/// let mut w = JsonWriter::new(&mut output);
+/// w.object(|w| {
+///     w.field("name")?.string("indenter")?;
+///     w.field("versions")?.array(|w| {
+///         w.value(1)?;
+///         w.null()
+///     })
+/// })?;
+///
+/// assert_eq!(
+///     output,
+///     "{\n  \"name\": \"indenter\",\n  \"versions\": [\n    1,\n    null\n  ]\n}"
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct JsonWriter<W> {
+    inner: W,
+    unit: &'static str,
+    depth: Indent,
+    /// Whether no entry has been written in the current scope yet
+    first: bool,
+    /// Whether a key has been written and its value is next
+    after_key: bool,
+}
+
+impl<W: fmt::Write> JsonWriter<W> {
+    /// Construct a writer that indents nested scopes with two spaces
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            unit: "  ",
+            depth: Indent::ZERO,
+            first: true,
+            after_key: false,
+        }
+    }
+
+    /// Sets the string repeated once per level of nesting
+    pub fn with_str(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Write an object, whose fields are written by `f`
+    pub fn object<F>(&mut self, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Self) -> fmt::Result,
+    {
+        self.scope('{', '}', f)
+    }
+
+    /// Write an array, whose values are written by `f`
+    pub fn array<F>(&mut self, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Self) -> fmt::Result,
+    {
+        self.scope('[', ']', f)
+    }
+
+    /// Start an object field called `key`, the value is written by the next call on the returned
+    /// writer
+    pub fn field(&mut self, key: &str) -> Result<&mut Self, fmt::Error> {
+        self.string(key)?;
+        self.inner.write_str(": ")?;
+        self.after_key = true;
+        Ok(self)
+    }
+
+    /// Write a quoted, escaped string value
+    pub fn string(&mut self, value: &str) -> fmt::Result {
+        self.begin_value()?;
+        write_quoted(&mut self.inner, value)
+    }
+
+    /// Write a value as is, such as a number or a boolean
+    pub fn value(&mut self, value: impl fmt::Display) -> fmt::Result {
+        self.begin_value()?;
+        write!(self.inner, "{}", value)
+    }
+
+    /// Write `null`
+    pub fn null(&mut self) -> fmt::Result {
+        self.value("null")
+    }
+
+    /// Consumes this writer, returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn scope<F>(&mut self, open: char, close: char, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Self) -> fmt::Result,
+    {
+        self.begin_value()?;
+        self.inner.write_char(open)?;
+
+        let first = self.first;
+        self.first = true;
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        let empty = core::mem::replace(&mut self.first, first);
+        result?;
+
+        if !empty {
+            self.newline()?;
+        }
+
+        self.inner.write_char(close)
+    }
+
+    /// Writes the separator and line break in front of a value, unless it follows a key
+    fn begin_value(&mut self) -> fmt::Result {
+        if self.after_key {
+            self.after_key = false;
+            return Ok(());
+        }
+
+        if self.depth == Indent::ZERO {
+            return Ok(());
+        }
+
+        if !self.first {
+            self.inner.write_char(',')?;
+        }
+
+        self.first = false;
+        self.newline()
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        write!(self.inner, "\n{}", Indentation::new(self.depth, self.unit))
+    }
+}
+
+/// Writes `s` as a JSON string literal
+fn write_quoted(f: &mut dyn fmt::Write, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    JsonEscape::new(&mut *f).write_str(s)?;
+    f.write_char('"')
+}
+
+/// A writer that escapes everything written to it for the inside of a JSON string
+///
+/// Quotes, backslashes and control characters, including newlines, are escaped, so the output
+/// can be placed between a pair of quotes as is. Write an indented report through it to pack the
+/// whole report into a single JSON string field, without building the report in a buffer first.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, JsonEscape};
+///
+/// let mut output = String::from("{\"report\":\"");
+/// write!(
+///     indented(&mut JsonEscape::new(&mut output)).with_str("    "),
+///     "error:\nsaid \"no\""
+/// )?;
+/// output.push_str("\"}");
+///
+/// assert_eq!(output, r#"{"report":"    error:\n    said \"no\""}"#);
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct JsonEscape<'a, W: ?Sized> {
+    inner: &'a mut W,
+}
+
+impl<'a, W: ?Sized> JsonEscape<'a, W> {
+    /// Construct a writer that escapes text written to `inner`
+    pub fn new(inner: &'a mut W) -> Self {
+        Self { inner }
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for JsonEscape<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+        while let Some(pos) = rest.find(|c: char| c == '"' || c == '\\' || c.is_control()) {
+            let (plain, tail) = rest.split_at(pos);
+            self.inner.write_str(plain)?;
+
+            let c = tail.chars().next().unwrap_or_default();
+            match c {
+                '"' => self.inner.write_str("\\\"")?,
+                '\\' => self.inner.write_str("\\\\")?,
+                '\n' => self.inner.write_str("\\n")?,
+                '\r' => self.inner.write_str("\\r")?,
+                '\t' => self.inner.write_str("\\t")?,
+                _ => write!(self.inner, "\\u{:04x}", c as u32)?,
+            }
+            rest = &tail[c.len_utf8()..];
+        }
+
+        self.inner.write_str(rest)
+    }
+}
+
+/// Helper struct for writing a value as a quoted JSON string
+///
+/// See `json_string` for details.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonString<T> {
+    value: T,
+}
+
+/// Create a `JsonString` that displays `value` as a quoted and escaped JSON string
+///
+/// ```rust
+/// use indenter::{indent_display, json_string};
+///
+/// let report = indent_display("error:\n\tcaused by", "    ");
+///
+/// assert_eq!(
+///     format!(r#"{{"report":{}}}"#, json_string(report)),
+///     r#"{"report":"    error:\n    \tcaused by"}"#
+/// );
+/// ```
+pub fn json_string<T: fmt::Display>(value: T) -> JsonString<T> {
+    JsonString { value }
+}
+
+impl<T: fmt::Display> fmt::Display for JsonString<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        write!(JsonEscape::new(&mut *f), "{}", self.value)?;
+        f.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn escape_across_writes() {
+        let mut output = String::new();
+        let mut escape = JsonEscape::new(&mut output);
+        escape.write_str("a\u{1}").unwrap();
+        escape.write_str("\\").unwrap();
+
+        assert_eq!(output, "a\\u0001\\\\");
+    }
+
+    #[test]
+    fn empty_scopes() {
+        let mut output = String::new();
+        JsonWriter::new(&mut output)
+            .array(|w| {
+                w.object(|_| Ok(()))?;
+                w.array(|_| Ok(()))
+            })
+            .unwrap();
+
+        assert_eq!(output, "[\n  {},\n  []\n]");
+    }
+
+    #[test]
+    fn escapes_strings() {
+        let mut output = String::new();
+        JsonWriter::new(&mut output)
+            .with_str("\t")
+            .object(|w| w.field("a\"b")?.string("line\n\\\u{1}"))
+            .unwrap();
+
+        assert_eq!(output, "{\n\t\"a\\\"b\": \"line\\n\\\\\\u0001\"\n}");
+    }
+}
@@ -0,0 +1,332 @@
+use crate::Format;
+use core::fmt;
+use serde_json::ser::{CompactFormatter, Formatter};
+use std::io;
+
+/// A `serde_json` formatter that pretty prints with this crate's indentation formats
+///
+/// # Explanation
+///
+/// `serde_json::ser::PrettyFormatter` can only repeat a fixed byte string per nesting level. This
+/// formatter repeats an arbitrary indentation unit, such as a tab, per level and can additionally
+/// render a `Format` as a gutter in front of every line of the document, for example to number
+/// the lines of a JSON payload embedded in a report.
+///
+/// ```rust
+/// use indenter::{Format, JsonFormatter};
+/// use serde::Serialize;
+///
+/// let value = serde_json::json!({ "a": [1] });
+/// let mut output = Vec::new();
+/// let formatter = JsonFormatter::new()
+///     .with_str("\t")
+///     .with_gutter(Format::Uniform { indentation: "| " });
+/// let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+/// value.serialize(&mut serializer).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "| {\n| \t\"a\": [\n| \t\t1\n| \t]\n| }"
+/// );
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct JsonFormatter<'a> {
+    indentation: &'a str,
+    gutter: Option<Format<'a>>,
+    depth: usize,
+    line: usize,
+    started: bool,
+    has_value: bool,
+}
+
+impl<'a> JsonFormatter<'a> {
+    /// Construct a formatter that indents nested values with two spaces, like `PrettyFormatter`
+    pub fn new() -> Self {
+        Self {
+            indentation: "  ",
+            gutter: None,
+            depth: 0,
+            line: 0,
+            started: false,
+            has_value: false,
+        }
+    }
+
+    /// Sets the string repeated once per nesting level
+    pub fn with_str(mut self, indentation: &'a str) -> Self {
+        self.indentation = indentation;
+        self
+    }
+
+    /// Render `format` as a gutter in front of every line of the document
+    pub fn with_gutter(mut self, format: Format<'a>) -> Self {
+        self.gutter = Some(format);
+        self
+    }
+
+    fn gutter<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        let format = match &mut self.gutter {
+            Some(format) => format,
+            None => return Ok(()),
+        };
+
+        let mut bridge = Bridge {
+            inner: writer,
+            error: None,
+        };
+
+        format
+            .render_gutter(self.line, &mut bridge)
+            .map_err(|fmt::Error| {
+                bridge
+                    .error
+                    .take()
+                    .unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, "formatter error"))
+            })
+    }
+
+    /// Writes the gutter of the first line before the first token of the document
+    fn start<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if self.started {
+            return Ok(());
+        }
+
+        self.started = true;
+        self.gutter(writer)
+    }
+
+    fn newline<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        writer.write_all(b"\n")?;
+        self.line += 1;
+        self.gutter(writer)?;
+
+        for _ in 0..self.depth {
+            writer.write_all(self.indentation.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    fn begin<W>(&mut self, writer: &mut W, open: &[u8]) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.start(writer)?;
+        self.depth += 1;
+        self.has_value = false;
+        writer.write_all(open)
+    }
+
+    fn end<W>(&mut self, writer: &mut W, close: &[u8]) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.depth -= 1;
+
+        if self.has_value {
+            self.newline(writer)?;
+        }
+
+        writer.write_all(close)
+    }
+
+    fn begin_entry<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if !first {
+            writer.write_all(b",")?;
+        }
+
+        self.newline(writer)
+    }
+}
+
+impl Default for JsonFormatter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adapts an `io::Write` to `fmt::Write` so formats can render gutters into it
+struct Bridge<'w, W: ?Sized> {
+    inner: &'w mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: ?Sized + io::Write> fmt::Write for Bridge<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Forwards scalar writes to `CompactFormatter` after writing the first gutter if needed
+macro_rules! scalars {
+    ($($method:ident($($value:ident: $ty:ty)?),)*) => {
+        $(
+            fn $method<W>(&mut self, writer: &mut W $(, $value: $ty)?) -> io::Result<()>
+            where
+                W: ?Sized + io::Write,
+            {
+                self.start(writer)?;
+                CompactFormatter.$method(writer $(, $value)?)
+            }
+        )*
+    };
+}
+
+impl Formatter for JsonFormatter<'_> {
+    scalars! {
+        write_null(),
+        write_bool(value: bool),
+        write_i8(value: i8),
+        write_i16(value: i16),
+        write_i32(value: i32),
+        write_i64(value: i64),
+        write_i128(value: i128),
+        write_u8(value: u8),
+        write_u16(value: u16),
+        write_u32(value: u32),
+        write_u64(value: u64),
+        write_u128(value: u128),
+        write_f32(value: f32),
+        write_f64(value: f64),
+        write_number_str(value: &str),
+        begin_string(),
+        write_raw_fragment(value: &str),
+    }
+
+    fn begin_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.begin(writer, b"[")
+    }
+
+    fn end_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.end(writer, b"]")
+    }
+
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.begin_entry(writer, first)
+    }
+
+    fn end_array_value<W>(&mut self, _writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.has_value = true;
+        Ok(())
+    }
+
+    fn begin_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.begin(writer, b"{")
+    }
+
+    fn end_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.end(writer, b"}")
+    }
+
+    fn begin_object_key<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.begin_entry(writer, first)
+    }
+
+    fn begin_object_value<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        writer.write_all(b": ")
+    }
+
+    fn end_object_value<W>(&mut self, _writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.has_value = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use serde_json::json;
+    use std::fmt::Write;
+
+    fn to_string<T: Serialize>(value: &T, formatter: JsonFormatter<'_>) -> String {
+        let mut output = Vec::new();
+        let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+        value.serialize(&mut serializer).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn matches_pretty_formatter() {
+        let value = json!({ "a": [1, { "b": null }, []], "c": {}, "d": "e" });
+
+        assert_eq!(
+            to_string(&value, JsonFormatter::new()),
+            serde_json::to_string_pretty(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn deep_nesting_with_tabs() {
+        let value = json!([[[[true]]]]);
+        let expected = "[\n\t[\n\t\t[\n\t\t\t[\n\t\t\t\ttrue\n\t\t\t]\n\t\t]\n\t]\n]";
+
+        assert_eq!(
+            to_string(&value, JsonFormatter::new().with_str("\t")),
+            expected
+        );
+    }
+
+    #[test]
+    fn escaped_newlines_do_not_start_lines() {
+        let value = json!({ "multi\nline": "first\nsecond\t\"quoted\"" });
+        let mut gutter = |line, f: &mut dyn Write| write!(f, "{}| ", line);
+        let formatter = JsonFormatter::new().with_gutter(Format::Custom {
+            inserter: &mut gutter,
+        });
+
+        assert_eq!(
+            to_string(&value, formatter),
+            "0| {\n1|   \"multi\\nline\": \"first\\nsecond\\t\\\"quoted\\\"\"\n2| }"
+        );
+    }
+
+    #[test]
+    fn scalar_document_gets_gutter() {
+        let formatter = JsonFormatter::new().with_gutter(Format::Numbered { ind: 1 });
+
+        assert_eq!(to_string(&"text", formatter), "   1: \"text\"");
+    }
+}
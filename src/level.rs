@@ -0,0 +1,177 @@
+use crate::spaces::{write_run, SPACES, TABS};
+use core::fmt;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+
+/// An indentation depth
+///
+/// # Explanation
+///
+/// Adding to an `Indent` nests one or more levels deeper, subtracting from it dedents. Both
+/// saturate instead of overflowing, so an unbalanced dedent stops at the left margin rather than
+/// wrapping around or panicking. Writers that track depth use this type so that depth bookkeeping
+/// is not just bare `usize` arithmetic.
+///
+/// ```rust
+/// use indenter::Indent;
+///
+/// let mut depth = Indent::default();
+/// depth += 1;
+///
+/// assert_eq!(depth + 2, Indent(3));
+/// assert_eq!(depth - 5, Indent(0));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Indent(pub usize);
+
+impl Indent {
+    /// The left margin
+    pub const ZERO: Indent = Indent(0);
+
+    /// The number of levels of this indentation depth
+    pub fn level(self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for Indent {
+    fn from(level: usize) -> Self {
+        Indent(level)
+    }
+}
+
+impl From<Indent> for usize {
+    fn from(indent: Indent) -> Self {
+        indent.0
+    }
+}
+
+impl Add<usize> for Indent {
+    type Output = Indent;
+
+    fn add(self, levels: usize) -> Indent {
+        Indent(self.0.saturating_add(levels))
+    }
+}
+
+impl Sub<usize> for Indent {
+    type Output = Indent;
+
+    fn sub(self, levels: usize) -> Indent {
+        Indent(self.0.saturating_sub(levels))
+    }
+}
+
+impl AddAssign<usize> for Indent {
+    fn add_assign(&mut self, levels: usize) {
+        *self = *self + levels;
+    }
+}
+
+impl SubAssign<usize> for Indent {
+    fn sub_assign(&mut self, levels: usize) {
+        *self = *self - levels;
+    }
+}
+
+/// A run of indentation made of a unit repeated `level` times
+///
+/// # Explanation
+///
+/// This type implements `Display` by writing `unit` once per level directly to the formatter, so
+/// a prefix can be written by hand, for example before delegating to an `Indented`, without
+/// building it in a `String` first. Units made only of spaces or only of tabs are written from a
+/// static run of that character in large chunks, so deep nesting takes a handful of writes rather
+/// than one per level.
+///
+/// ```rust
+/// use indenter::{Indent, Indentation};
+///
+/// let indentation = Indentation { level: Indent(2), unit: "  " };
+///
+/// assert_eq!(format!("{}fn main() {{}}", indentation), "    fn main() {}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Indentation<'a> {
+    /// The number of times `unit` is repeated
+    pub level: Indent,
+    /// The string written once per level
+    pub unit: &'a str,
+}
+
+impl<'a> Indentation<'a> {
+    /// Construct an indentation of `level` repetitions of `unit`
+    pub fn new(level: Indent, unit: &'a str) -> Self {
+        Self { level, unit }
+    }
+}
+
+impl fmt::Display for Indentation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pool = match self.unit.as_bytes().first() {
+            Some(b' ') => SPACES,
+            Some(b'\t') => TABS,
+            _ => "",
+        };
+
+        if !pool.is_empty() && self.unit.bytes().all(|b| b == pool.as_bytes()[0]) {
+            if let Some(count) = self.level.0.checked_mul(self.unit.len()) {
+                return write_run(f, pool, count);
+            }
+        }
+
+        for _ in 0..self.level.0 {
+            f.write_str(self.unit)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::ToString;
+    use core::fmt::Write;
+
+    #[test]
+    fn zero_level() {
+        assert_eq!(Indentation::new(Indent::ZERO, "\t").to_string(), "");
+    }
+
+    #[test]
+    fn repeats_unit() {
+        assert_eq!(Indentation::new(Indent(3), "\t").to_string(), "\t\t\t");
+    }
+
+    #[test]
+    fn deep_levels_are_chunked() {
+        struct Count(usize, usize);
+
+        impl fmt::Write for Count {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += 1;
+                self.1 += s.len();
+                Ok(())
+            }
+        }
+
+        let mut count = Count(0, 0);
+        write!(count, "{}", Indentation::new(Indent(40), "    ")).unwrap();
+        assert_eq!((count.0, count.1), (3, 160));
+
+        assert_eq!(Indentation::new(Indent(2), " \t").to_string(), " \t \t");
+    }
+
+    #[test]
+    fn saturating_arithmetic() {
+        let mut depth = Indent(usize::MAX);
+        depth += 1;
+        assert_eq!(depth, Indent(usize::MAX));
+
+        depth -= usize::MAX;
+        depth -= 1;
+        assert_eq!(depth, Indent::ZERO);
+    }
+}
@@ -98,7 +98,6 @@
     rust_2018_idioms,
     unreachable_pub,
     bad_style,
-    const_err,
     dead_code,
     improper_ctypes,
     non_shorthand_field_patterns,
@@ -106,7 +105,6 @@
     overflowing_literals,
     path_statements,
     patterns_in_fns_without_body,
-    private_in_public,
     unconditional_recursion,
     unused,
     unused_allocation,
@@ -114,11 +112,231 @@
     unused_parens,
     while_true
 )]
+use crate::buffer::StackBuffer;
 use core::fmt;
+use core::mem;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "std")]
+mod assert;
+mod auto;
+mod boxed;
+mod buffer;
+mod callout;
+#[cfg(feature = "alloc")]
+mod capture;
+mod caret;
+#[cfg(feature = "alloc")]
+mod code;
+#[cfg(feature = "std")]
+mod collect;
+mod color;
+mod column;
+mod commit;
+mod connectors;
+#[cfg(feature = "alloc")]
+mod console;
+mod continuation;
+mod debug;
+mod definitions;
+mod detect;
+mod diagnostic;
+mod diff;
+mod display;
+#[cfg(feature = "std")]
+mod editorconfig;
+#[cfg(feature = "std")]
+mod env;
+#[cfg(feature = "tracing")]
+mod fields;
+mod flowed;
+mod gutter;
+mod help;
+mod hexdump;
+mod html;
+mod indented_owned;
+#[cfg(feature = "std")]
+mod io;
+mod json;
+#[cfg(all(feature = "std", feature = "serde_json"))]
+mod json_formatter;
+mod level;
+#[cfg(feature = "alloc")]
+mod line_buffer;
+mod lines;
+mod literal;
+mod markdown;
+mod normalize;
+mod options;
+#[cfg(feature = "alloc")]
+mod owned;
+mod pad;
+mod pretty;
+mod preview;
+#[cfg(all(feature = "std", feature = "log"))]
+mod record;
+mod reindent;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod scope;
+mod section;
+mod sexpr;
+mod shared;
+mod snippet;
+mod spaces;
+mod status;
+#[cfg(feature = "alloc")]
+mod string;
+mod style;
+#[cfg(feature = "alloc")]
+mod tabs;
+mod width;
+mod wrap;
+#[cfg(feature = "alloc")]
+mod xml;
+
+#[cfg(feature = "std")]
+pub use assert::assert_lines_eq;
+pub use auto::{auto_indent, AutoIndent};
+pub use boxed::{boxed, Border, Boxed};
+pub use callout::{Callout, Severity};
+#[cfg(feature = "alloc")]
+pub use capture::{LineCallback, LineCapture};
+pub use caret::Caret;
+#[cfg(feature = "alloc")]
+pub use code::{CodeWriter, Language};
+#[cfg(feature = "std")]
+pub use collect::{SectionBuffer, SectionCollector};
+pub use color::ColorChoice;
+pub use column::{track_columns, ColumnTracker};
+pub use commit::{commit_message, CommitMessage};
+pub use connectors::{Connectors, Label};
+#[cfg(feature = "wasm")]
+pub use console::WebConsole;
+#[cfg(feature = "alloc")]
+pub use console::{ConsoleSink, ConsoleWriter};
+pub use continuation::{Continued, LogContinuation};
+pub use debug::{debug_list, debug_struct, debug_tuple, DebugList, DebugStruct, DebugTuple};
+pub use definitions::DefinitionList;
+pub use detect::{detect_indent, IndentUnit};
+pub use diagnostic::Diagnostic;
+pub use diff::{DiffLine, HunkWriter};
+pub use display::{
+    indent_debug, indent_debug_with, indent_display, indent_display_with, indent_fn, DisplayExt,
+    IndentDebug, IndentDisplay, IndentFn,
+};
+#[cfg(feature = "tracing")]
+pub use fields::IndentedFields;
+pub use flowed::{quote_flowed, FlowedQuote};
+pub use gutter::GutterState;
+pub use help::HelpWriter;
+pub use hexdump::HexDump;
+pub use html::{HtmlGutter, HtmlWriter};
+pub use indented_owned::IndentedOwned;
+#[cfg(feature = "derive")]
+pub use indenter_derive::{IndentedDisplay, IndentedReport};
+#[cfg(feature = "std")]
+pub use io::{indented_io, IoIndented};
+pub use json::{json_string, JsonEscape, JsonString, JsonWriter};
+#[cfg(all(feature = "std", feature = "serde_json"))]
+pub use json_formatter::JsonFormatter;
+pub use level::{Indent, Indentation};
+#[cfg(feature = "alloc")]
+pub use line_buffer::LineBuffer;
+pub use lines::{indent_lines, IndentLines, IndentLinesIter, IndentedLine};
+pub use literal::{rust_string, RustString};
+pub use markdown::{MarkdownList, MarkdownWriter};
+pub use normalize::{normalize_indent, NormalizedIndent};
+pub use options::{indented_formatter, FormatterOptions};
+#[cfg(feature = "alloc")]
+pub use owned::OwnedFormat;
+pub use pad::{padded, Padded};
+pub use pretty::{IndentWriter, Pretty, PrettyPrint};
+#[cfg(all(feature = "std", feature = "proc-macro2"))]
+pub use preview::token_preview;
+pub use preview::{code_preview, CodePreview};
+#[cfg(all(feature = "std", feature = "log"))]
+pub use record::IndentedRecord;
+pub use reindent::{reindent_debug, Reindent, ReindentDebug};
+#[cfg(feature = "std")]
+pub use report::ErrorReport;
+#[cfg(feature = "std")]
+pub use scope::{current_depth, pop_scope, push_scope, ScopeGuard, Scoped};
+pub use section::Section;
+pub use sexpr::{SexprAlign, SexprWriter};
+#[cfg(feature = "std")]
+pub use shared::MutexWriter;
+pub use shared::RefCellWriter;
+pub use snippet::{write_snippet, SnippetOptions};
+pub use spaces::{indented_spaces, SpacesIndented};
+pub use status::Status;
+#[cfg(feature = "alloc")]
+pub use string::{dedent, indent, indent_all_by, indent_all_with, indent_in_place, indent_where};
+pub use style::{IndentOptions, LineEnding};
+#[cfg(feature = "alloc")]
+pub use tabs::TabWriter;
+pub use width::display_width;
+pub use wrap::{reflow, wrap, Wrapped, LIST_MARKERS};
+#[cfg(feature = "alloc")]
+pub use xml::XmlWriter;
+
+/// The indentation `indented` and the other indenters insert unless told otherwise
+///
+/// This is four spaces, or with the `default-spaces-2` or `default-tabs` feature two spaces or a
+/// tab, so a project can change the default everywhere without configuring every indenter. If
+/// more than one of these features ends up enabled, for example because two dependencies ask
+/// for different ones or with `--all-features`, the default of four spaces is used.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, DEFAULT_INDENTATION};
+///
+/// let mut output = String::new();
+/// write!(indented(&mut output), "a")?;
+///
+/// assert_eq!(output, format!("{}a", DEFAULT_INDENTATION));
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+pub const DEFAULT_INDENTATION: &str = if cfg!(all(
+    feature = "default-tabs",
+    not(feature = "default-spaces-2"),
+    not(feature = "default-spaces-4")
+)) {
+    "\t"
+} else if cfg!(all(
+    feature = "default-spaces-2",
+    not(feature = "default-tabs"),
+    not(feature = "default-spaces-4")
+)) {
+    "  "
+} else {
+    "    "
+};
+
+/// The format `indented` and the other indenters use unless told otherwise, the same as
+/// `Format::DEFAULT`
+///
+/// This is `Format::Uniform` with `DEFAULT_INDENTATION`, so it follows the `default-*` features.
+pub const DEFAULT_FORMAT: Format<'static> = Format::DEFAULT;
 
 /// The set of supported formats for indentation
+///
+/// New formats may be added in minor releases, so matches on a `Format` need a wildcard arm.
 #[allow(missing_debug_implementations)]
+#[non_exhaustive]
 pub enum Format<'a> {
+    /// Pass all output through unchanged
+    ///
+    /// Unlike `Uniform` with an empty string, an indenter with this format does not look for
+    /// newlines at all, so it costs nothing over writing to the inner writer directly. None of the
+    /// other options of the indenter apply either, and the line count is not updated.
+    None,
     /// Insert uniform indentation before every line
     ///
     /// This format takes a static string as input and inserts it after every newline
@@ -134,15 +352,145 @@ pub enum Format<'a> {
         /// The index to insert before the first line of output
         ind: usize,
     },
+    /// Inserts a number before the first line, in a gutter sized for the largest index
+    ///
+    /// `Format::Numbered` right aligns indices to four digits. Giving the largest index that will
+    /// be written instead sizes the gutter to fit that index, so every element of a chain gets the
+    /// same gutter width however many elements it has.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::{indented, Format};
+    ///
+    /// let mut output = String::new();
+    /// for ind in [7, 117] {
+    ///     let format = Format::NumberedMax { ind, max: 120 };
+    ///     writeln!(indented(&mut output).with_format(format), "error\ncontext")?;
+    /// }
+    ///
+    /// assert_eq!(output, "  7: error\n     context\n117: error\n     context\n");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    NumberedMax {
+        /// The index to insert before the first line of output
+        ind: usize,
+        /// The largest index the gutter should have room for
+        max: usize,
+    },
     /// A custom indenter which is executed after every newline
     ///
     /// Custom indenters are passed the current line number and the buffer to be written to as args
     Custom {
         /// The custom indenter
-        inserter: &'a mut Inserter,
+        inserter: &'a mut (dyn FnMut(usize, &mut dyn fmt::Write) -> fmt::Result + 'a),
+    },
+    /// A custom indenter which is executed after every newline, without needing mutable access
+    ///
+    /// This is the same as `Format::Custom` for inserters that are pure functions of the line
+    /// number, which can then be shared, for example from a configuration borrowed by several
+    /// indenters at once.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::{indented, Format};
+    ///
+    /// let inserter = |line, f: &mut dyn Write| write!(f, "{}> ", line + 1);
+    ///
+    /// let mut output = String::new();
+    /// writeln!(indented(&mut output).with_format(Format::CustomFn { inserter: &inserter }), "a")?;
+    /// write!(indented(&mut output).with_format(Format::CustomFn { inserter: &inserter }), "b\nc")?;
+    ///
+    /// assert_eq!(output, "1> a\n1> b\n2> c");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    CustomFn {
+        /// The custom indenter
+        inserter: &'a (dyn Fn(usize, &mut dyn fmt::Write) -> fmt::Result + 'a),
+    },
+    /// A custom indenter which is also passed the options of the formatter being indented
+    ///
+    /// The options are those captured by `indented_formatter`, or the defaults of a plain `{}`
+    /// for any other indenter, so the inserter can take the width, precision and flags of the
+    /// outer format string into account.
+    ///
+    /// ```rust
+    /// use core::fmt::{self, Write};
+    /// use indenter::{indented_formatter, Format, FormatterOptions};
+    ///
+    /// struct Quoted<'a>(&'a str);
+    ///
+    /// impl fmt::Display for Quoted<'_> {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         let mut inserter = |_, options: &FormatterOptions, f: &mut dyn Write| {
+    ///             f.write_str(if options.alternate { "| " } else { "> " })
+    ///         };
+    ///         let format = Format::CustomWithOptions { inserter: &mut inserter };
+    ///
+    ///         write!(indented_formatter(f).with_format(format), "{}", self.0)
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(format!("{}", Quoted("a\nb")), "> a\n> b");
+    /// assert_eq!(format!("{:#}", Quoted("a\nb")), "| a\n| b");
+    /// ```
+    CustomWithOptions {
+        /// The custom indenter
+        inserter:
+            &'a mut (dyn FnMut(usize, &FormatterOptions, &mut dyn fmt::Write) -> fmt::Result + 'a),
+    },
+    /// A custom indenter which can also skip or replace the line it is indenting
+    ///
+    /// Filters are passed the current line number, the text of the line and the buffer to be
+    /// written to as args, and return what should happen to the original line. The text is only
+    /// the part of the line passed to the same call to `write_str` as the newline before it, so a
+    /// line written in several pieces is only partially visible, and it is empty for eager
+    /// indentation and `Indented::finish`.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::{indented, Format, LineAction};
+    ///
+    /// let mut filter = |_, line: &str, f: &mut dyn Write| {
+    ///     if line.starts_with("password") {
+    ///         f.write_str("    password: <redacted>")?;
+    ///         Ok(LineAction::Replaced)
+    ///     } else if line.starts_with("debug") {
+    ///         Ok(LineAction::Skip)
+    ///     } else {
+    ///         f.write_str("    ")?;
+    ///         Ok(LineAction::Keep)
+    ///     }
+    /// };
+    ///
+    /// let mut output = String::new();
+    /// write!(
+    ///     indented(&mut output).with_format(Format::Filter { filter: &mut filter }),
+    ///     "user: ferris\ndebug: true\npassword: hunter2\n"
+    /// )?;
+    ///
+    /// assert_eq!(output, "    user: ferris\n    password: <redacted>\n");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    Filter {
+        /// The filter
+        filter: &'a mut LineFilter<'a>,
     },
 }
 
+/// What a `Format::Filter` wants done with the line it was called for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LineAction {
+    /// Write the line after whatever the filter wrote, the default
+    #[default]
+    Keep,
+    /// Leave the line out of the output, along with the newline ending it
+    Skip,
+    /// Leave the line out of the output because the filter already wrote a replacement for it
+    ///
+    /// The newline ending the line is still written.
+    Replaced,
+}
+
 /// Helper struct for efficiently indenting multi line display implementations
 ///
 /// # Explanation
@@ -152,40 +500,222 @@ pub enum Format<'a> {
 /// lets it intercept each piece of output as its being written to the output buffer. It then
 /// splits on newlines giving slices into the original string. Finally we alternate writing these
 /// lines and the specified indentation to the output buffer.
+///
+/// # State
+///
+/// By default the indentation of a line is written lazily, right before the first text of that
+/// line, so an indenter that has just written a newline has not written anything for the next
+/// line yet. Anything written directly to the inner writer at that point ends up in front of the
+/// indentation. Use `eager` to write the indentation as soon as the newline is written instead,
+/// which makes it safe to alternate between writing through the indenter and writing to the inner
+/// writer directly, as long as the direct writes do not contain newlines themselves.
 #[allow(missing_debug_implementations)]
 pub struct Indented<'a, D: ?Sized> {
     inner: &'a mut D,
     needs_indent: bool,
+    skip_empty: bool,
+    /// The line of the output being written, counted across calls to `write_str`
+    line: usize,
+    /// Whether a byte order mark at the start of the next non-empty write should be dropped
+    strip_bom: bool,
+    /// Whether indentation is written as soon as a line starts instead of before its first text
+    eager: bool,
+    /// Whether indentation is rendered into a buffer before being written
+    atomic_gutter: bool,
+    /// Whether the indentation of the second line is reused for every line after it
+    cache_gutter: bool,
+    /// The indentation of the second line, once rendered
+    gutter_cache: Option<StackBuffer>,
+    trailing_newline: TrailingNewline,
+    /// Whether a newline has been held back in case it turns out to be the last one
+    pending_newline: bool,
+    /// The options of the formatter this indenter was created for, if any
+    options: Option<FormatterOptions>,
+    /// What the format asked to be done with the current line
+    action: LineAction,
+    /// The number of lines at the start of the output that are not indented
+    skip_lines: usize,
+    /// An existing prefix that is replaced by the indentation instead of indented further
+    collapse_prefix: Option<&'a str>,
+    /// Writes the indentation of each line in a style, written unstyled if unset
+    stylize: Option<&'a mut Stylize<'a>>,
+    /// Whether `stylize` is used, or the indentation is written plain
+    color: bool,
+    /// Decides which lines are indented, all of them if unset
+    predicate: Option<&'a mut LinePredicate<'a>>,
     format: Format<'a>,
 }
 
+/// What an `Indented` does with a newline at the very end of its output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TrailingNewline {
+    /// Write the newline and leave the line after it empty, the default
+    #[default]
+    Keep,
+    /// Leave the newline out
+    ///
+    /// Newlines are held back until more output follows them, so the last one is never written.
+    Drop,
+    /// Write the newline and indent the line after it
+    ///
+    /// The indentation is written by `Indented::finish`, since only then is it known that no more
+    /// output follows.
+    Indent,
+}
+
 /// A callback for `Format::Custom` used to insert indenation after a new line
 ///
 /// The first argument is the line number within the output, starting from 0
 pub type Inserter = dyn FnMut(usize, &mut dyn fmt::Write) -> fmt::Result;
 
+/// An `Inserter` that can be sent to other threads
+pub type InserterSend = dyn FnMut(usize, &mut dyn fmt::Write) -> fmt::Result + Send;
+
+/// An `Inserter` that can be sent to and shared between threads
+///
+/// `Format::Custom` borrows an inserter without any auto trait bounds, so a `Format` cannot be
+/// stored in a type that has to be `Send` or `Sync`. Store the inserter behind this alias instead
+/// and borrow it into a `Format` when writing.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, Format, InserterSync};
+///
+/// struct Handler {
+///     inserter: Box<InserterSync>,
+/// }
+///
+/// fn assert_send_sync<T: Send + Sync>(_: &T) {}
+///
+/// let mut handler = Handler {
+///     inserter: Box::new(|line, f: &mut dyn Write| write!(f, "{:>2} | ", line)),
+/// };
+/// assert_send_sync(&handler);
+///
+/// let mut output = String::new();
+/// let format = Format::Custom {
+///     inserter: &mut *handler.inserter,
+/// };
+/// write!(indented(&mut output).with_format(format), "a\nb")?;
+///
+/// assert_eq!(output, " 0 | a\n 1 | b");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+pub type InserterSync = dyn FnMut(usize, &mut dyn fmt::Write) -> fmt::Result + Send + Sync;
+
+/// Compile time checks that the types carrying only data can be shared between threads
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Indent>();
+    assert_send_sync::<Indentation<'static>>();
+    assert_send_sync::<LineAction>();
+    assert_send_sync::<TrailingNewline>();
+    assert_send_sync::<FormatterOptions>();
+    assert_send_sync::<&'static mut InserterSync>();
+};
+
+/// A callback for `Format::Filter` used to insert indentation after a new line, or skip or replace
+/// the line
+///
+/// The arguments are the line number within the output, starting from 0, and the text of the line
+pub type LineFilter<'a> =
+    dyn FnMut(usize, &str, &mut dyn fmt::Write) -> Result<LineAction, fmt::Error> + 'a;
+
+/// A callback for `Indented::stylize_gutter` used to write the indentation of a line in a style
+///
+/// The first argument is the indentation as rendered by the format
+pub type Stylize<'a> = dyn FnMut(&str, &mut dyn fmt::Write) -> fmt::Result + 'a;
+
+/// A callback for `Indented::indent_if` used to decide whether a line is indented
+///
+/// The arguments are the line number within the output, starting from 0, and the text of the line
+pub type LinePredicate<'a> = dyn FnMut(usize, &str) -> bool + 'a;
+
+impl<'a> Format<'a> {
+    /// The format `indented` uses by default, `Format::Uniform` with `DEFAULT_INDENTATION`
+    pub const DEFAULT: Format<'a> = Format::Uniform {
+        indentation: DEFAULT_INDENTATION,
+    };
+}
+
 impl Format<'_> {
-    fn insert_indentation(&mut self, line: usize, f: &mut dyn fmt::Write) -> fmt::Result {
+    /// Write the indentation this format inserts before line `line` of the output, counting from 0
+    ///
+    /// This is the indentation an `Indented` with this format writes, with none of its options
+    /// applied, which is useful for measuring the gutter or rendering it somewhere else. Custom
+    /// inserters are called as usual, and a `Format::Filter` is passed an empty line.
+    ///
+    /// ```rust
+    /// use indenter::Format;
+    ///
+    /// let mut gutter = String::new();
+    /// let mut format = Format::Numbered { ind: 12 };
+    /// format.render_gutter(0, &mut gutter)?;
+    /// format.render_gutter(1, &mut gutter)?;
+    ///
+    /// assert_eq!(gutter, "  12:       ");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn render_gutter(&mut self, line: usize, f: &mut dyn fmt::Write) -> fmt::Result {
+        self.insert_line(line, "", &FormatterOptions::default(), f)
+            .map(|_| ())
+    }
+
+    /// Writes the indentation of a line with the given text and returns what to do with the line
+    fn insert_line(
+        &mut self,
+        line: usize,
+        text: &str,
+        options: &FormatterOptions,
+        f: &mut dyn fmt::Write,
+    ) -> Result<LineAction, fmt::Error> {
         match self {
-            Format::Uniform { indentation } => write!(f, "{}", indentation),
-            Format::Numbered { ind } => {
-                if line == 0 {
-                    write!(f, "{: >4}: ", ind)
-                } else {
-                    write!(f, "      ")
-                }
-            }
-            Format::Custom { inserter } => inserter(line, f),
+            Format::None => {}
+            Format::Uniform { indentation } => f.write_str(indentation)?,
+            Format::Numbered { ind } => write_numbered(line, *ind, 4, f)?,
+            Format::NumberedMax { ind, max } => write_numbered(line, *ind, digits(*max), f)?,
+            Format::Custom { inserter } => inserter(line, f)?,
+            Format::CustomFn { inserter } => inserter(line, f)?,
+            Format::CustomWithOptions { inserter } => inserter(line, options, f)?,
+            Format::Filter { filter } => return filter(line, text, f),
+        }
+
+        Ok(LineAction::Keep)
+    }
+
+    /// Borrow this format for use by a shorter lived indenter
+    pub(crate) fn reborrow(&mut self) -> Format<'_> {
+        match *self {
+            Format::None => Format::None,
+            Format::Uniform { indentation } => Format::Uniform { indentation },
+            Format::Numbered { ind } => Format::Numbered { ind },
+            Format::NumberedMax { ind, max } => Format::NumberedMax { ind, max },
+            Format::Custom { ref mut inserter } => Format::Custom {
+                inserter: &mut **inserter,
+            },
+            Format::CustomFn { inserter } => Format::CustomFn { inserter },
+            Format::CustomWithOptions { ref mut inserter } => Format::CustomWithOptions {
+                inserter: &mut **inserter,
+            },
+            Format::Filter { ref mut filter } => Format::Filter {
+                filter: &mut **filter,
+            },
         }
     }
 }
 
-impl<'a, D> Indented<'a, D> {
+impl<'a, D: ?Sized> Indented<'a, D> {
     /// Sets the format to `Format::Numbered` with the provided index
     pub fn ind(self, ind: usize) -> Self {
         self.with_format(Format::Numbered { ind })
     }
 
+    /// Sets the format to `Format::NumberedMax` with the provided index and largest index
+    pub fn ind_max(self, ind: usize, max: usize) -> Self {
+        self.with_format(Format::NumberedMax { ind, max })
+    }
+
     /// Sets the format to `Format::Uniform` with the provided static string
     pub fn with_str(self, indentation: &'static str) -> Self {
         self.with_format(Format::Uniform { indentation })
@@ -196,47 +726,578 @@ impl<'a, D> Indented<'a, D> {
         self.format = format;
         self
     }
+
+    /// Sets whether blank lines are left empty instead of being indented
+    ///
+    /// Blank lines are skipped by default for every format, so no line of output consists of
+    /// nothing but indentation. Disabling this indents them like any other line, which is what
+    /// `pad_adapter` does to match the standard library.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::indented;
+    ///
+    /// let mut output = String::new();
+    /// write!(indented(&mut output).ind(1).skip_empty(false), "a\n\nb")?;
+    ///
+    /// assert_eq!(output, "   1: a\n      \n      b");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn skip_empty(mut self, skip: bool) -> Self {
+        self.skip_empty = skip;
+        self
+    }
+
+    /// Sets whether a UTF-8 byte order mark at the very start of the output is dropped
+    ///
+    /// This is off by default. Turn it on when indenting file contents that are read verbatim, so
+    /// the byte order mark does not end up after the indentation of the first line.
+    pub fn strip_bom(mut self, strip: bool) -> Self {
+        self.strip_bom = strip;
+        self
+    }
+
+    /// Sets whether the indentation of a line is written as soon as the line starts
+    ///
+    /// An eager indenter writes the indentation of every line, including the first one, as soon as
+    /// it is known that the line exists, so blank lines are indented as well. With
+    /// `TrailingNewline::Drop` the indentation after a newline is still written lazily, since the
+    /// newline itself is held back. See the [state](Indented#state) section for details.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::indented;
+    ///
+    /// let mut output = String::new();
+    /// let mut indented = indented(&mut output).with_str("> ").eager(true);
+    /// indented.write_str("a\n")?;
+    /// // imagine writing inline styling straight to `output` here
+    /// indented.write_str("b")?;
+    ///
+    /// assert_eq!(output, "> a\n> b");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn eager(mut self, eager: bool) -> Self {
+        self.eager = eager;
+        self
+    }
+
+    /// Sets whether the indentation of each line is written to the inner writer in one piece
+    ///
+    /// The indentation is first rendered into a buffer on the stack that holds up to 64 bytes,
+    /// and only written out if rendering it succeeds, so a custom inserter that fails halfway
+    /// through does not leave part of its output behind. Indentation longer than the buffer fails
+    /// with `fmt::Error` without writing anything.
+    pub fn atomic_gutter(mut self, atomic: bool) -> Self {
+        self.atomic_gutter = atomic;
+        self
+    }
+
+    /// Sets whether the indentation of continuation lines is rendered once and reused
+    ///
+    /// Formats such as `Format::Numbered`, or custom inserters writing the same indentation level
+    /// on every line, give every line after the first the same indentation. With a gutter cache
+    /// the indentation of the second line is rendered into a buffer on the stack that holds up to
+    /// 64 bytes, and every later line gets a copy of it in a single write, without calling the
+    /// inserter again. Only enable this for formats that really do repeat themselves. If the
+    /// indentation does not fit in the buffer, the inserter is called again for that line and for
+    /// every line after it, as if the cache was disabled. `Format::Filter` is never cached.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::{indented, Format, Indent, Indentation};
+    ///
+    /// let mut calls = 0;
+    /// let mut inserter = |_, f: &mut dyn Write| {
+    ///     calls += 1;
+    ///     write!(f, "{}", Indentation::new(Indent(3), "  "))
+    /// };
+    ///
+    /// let mut output = String::new();
+    /// write!(
+    ///     indented(&mut output)
+    ///         .with_format(Format::Custom { inserter: &mut inserter })
+    ///         .cache_gutter(true),
+    ///     "a\nb\nc\nd"
+    /// )?;
+    ///
+    /// assert_eq!(output, "      a\n      b\n      c\n      d");
+    /// assert_eq!(calls, 2);
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn cache_gutter(mut self, cache: bool) -> Self {
+        self.cache_gutter = cache;
+        self
+    }
+
+    /// Sets the number of lines at the start of the output that are written without indentation
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::indented;
+    ///
+    /// let mut output = String::new();
+    /// write!(
+    ///     indented(&mut output).with_str("| ").skip_lines(2),
+    ///     "NAME  STATUS\n----  ------\nweb   running\ndb    stopped"
+    /// )?;
+    ///
+    /// assert_eq!(
+    ///     output,
+    ///     "NAME  STATUS\n----  ------\n| web   running\n| db    stopped"
+    /// );
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn skip_lines(mut self, lines: usize) -> Self {
+        self.skip_lines = lines;
+        self
+    }
+
+    /// Replace `prefix` at the start of a line with the indentation, instead of indenting it
+    ///
+    /// Content that was rendered with its own indentation, such as a section formatted by another
+    /// indenter, drifts further right every time it is nested again. With this set, a line that
+    /// starts with `prefix` has it removed before the indentation is written, so the content
+    /// keeps its depth. Lines without the prefix are indented as usual. Like the text given to
+    /// `Format::Filter`, only the part of a line passed to the same call to `write_str` as its
+    /// start is checked for the prefix.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::indented;
+    ///
+    /// let rendered = "    first\n    second\nthird";
+    /// let mut output = String::new();
+    /// write!(indented(&mut output).with_str("  | ").collapse_prefix("    "), "{}", rendered)?;
+    ///
+    /// assert_eq!(output, "  | first\n  | second\n  | third");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn collapse_prefix(mut self, prefix: &'a str) -> Self {
+        self.collapse_prefix = Some(prefix);
+        self
+    }
+
+    /// Write the indentation of each line through `stylize`, for example to color it
+    ///
+    /// The indentation is first rendered into a buffer on the stack that holds up to 64 bytes,
+    /// like with `atomic_gutter`, and then passed to `stylize` to be written in whatever style it
+    /// likes. This works with the styling types of crates such as `owo-colors` or `console`,
+    /// without constructing escape sequences by hand. Everything the format writes is styled,
+    /// including the replacement lines written by `Format::Filter`, except for empty indentation.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::indented;
+    ///
+    /// // `|gutter, f| write!(f, "{}", gutter.dimmed())` with `owo-colors`
+    /// let mut dimmed = |gutter: &str, f: &mut dyn Write| write!(f, "\x1b[2m{}\x1b[0m", gutter);
+    ///
+    /// let mut output = String::new();
+    /// write!(indented(&mut output).with_str("| ").stylize_gutter(&mut dimmed), "a\nb")?;
+    ///
+    /// assert_eq!(output, "\x1b[2m| \x1b[0ma\n\x1b[2m| \x1b[0mb");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn stylize_gutter(mut self, stylize: &'a mut Stylize<'a>) -> Self {
+        self.stylize = Some(stylize);
+        self
+    }
+
+    /// Sets whether the indentation is written through `stylize_gutter`, or plain
+    ///
+    /// Colors are enabled by default. Pass `ColorChoice::enabled` with the `std` feature to follow
+    /// the `NO_COLOR` and `CLICOLOR_FORCE` conventions, so the same code writes plain gutters when
+    /// the user turned colors off.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::indented;
+    ///
+    /// let mut dimmed = |gutter: &str, f: &mut dyn Write| write!(f, "\x1b[2m{}\x1b[0m", gutter);
+    ///
+    /// let mut output = String::new();
+    /// let mut indented = indented(&mut output)
+    ///     .with_str("| ")
+    ///     .stylize_gutter(&mut dimmed)
+    ///     .color(false);
+    /// write!(indented, "a\nb")?;
+    ///
+    /// assert_eq!(output, "| a\n| b");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+
+    /// Only indent the lines for which `predicate` returns true
+    ///
+    /// The predicate is passed the line number and the text of the line, with the same caveats as
+    /// `Format::Filter`: the text is only the part of the line written in the same call to
+    /// `write_str` as the newline before it, and it is empty for eager indentation.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::indented;
+    ///
+    /// let mut timestamped = |_, line: &str| !line.starts_with("2024-");
+    ///
+    /// let mut output = String::new();
+    /// let mut indented = indented(&mut output)
+    ///     .with_str("    ")
+    ///     .indent_if(&mut timestamped);
+    /// write!(indented, "2024-01-01 error: failed\ncaused by\n2024-01-02 retrying")?;
+    ///
+    /// assert_eq!(
+    ///     output,
+    ///     "2024-01-01 error: failed\n    caused by\n2024-01-02 retrying"
+    /// );
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn indent_if(mut self, predicate: &'a mut LinePredicate<'a>) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Sets what happens to a newline at the very end of the output
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::{indented, TrailingNewline};
+    ///
+    /// let mut output = String::new();
+    /// let mut indented = indented(&mut output)
+    ///     .with_str("    ")
+    ///     .trailing_newline(TrailingNewline::Indent);
+    /// writeln!(indented, "a")?;
+    /// indented.finish()?;
+    ///
+    /// assert_eq!(output, "    a\n    ");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn trailing_newline(mut self, policy: TrailingNewline) -> Self {
+        self.trailing_newline = policy;
+        self
+    }
+
+    /// Returns the options of the formatter this indenter was created for by
+    /// `indented_formatter`
+    pub fn options(&self) -> Option<&FormatterOptions> {
+        self.options.as_ref()
+    }
+
+    /// Returns whether the current line has been started, meaning its indentation has been written
+    ///
+    /// See the [state](Indented#state) section for when indentation is written.
+    pub fn started(&self) -> bool {
+        !self.needs_indent
+    }
+
+    /// Overrides whether the current line has been started
+    ///
+    /// Setting this to `true` tells the indenter that the current line was already begun by
+    /// writing to the inner writer directly, so no indentation is inserted before the next text.
+    /// Setting it to `false` makes the indenter write indentation before the next text even though
+    /// it is not at the start of a line.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::indented;
+    ///
+    /// let mut output = String::from("error: ");
+    /// let mut indented = indented(&mut output).with_str("    ");
+    /// indented.set_started(true);
+    /// write!(indented, "first\nsecond")?;
+    ///
+    /// assert_eq!(output, "error: first\n    second");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn set_started(&mut self, started: bool) {
+        self.needs_indent = !started;
+    }
+
+    /// Complete the output once nothing more will be written
+    ///
+    /// This is only needed with `TrailingNewline::Indent`, for which it writes the indentation of
+    /// the line after a trailing newline.
+    pub fn finish(&mut self) -> fmt::Result
+    where
+        D: fmt::Write,
+    {
+        let after_newline = self.needs_indent && self.line > 0;
+
+        if self.trailing_newline == TrailingNewline::Indent && after_newline {
+            self.indent("")?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the indentation of the current line, which starts with `text`
+    fn indent(&mut self, text: &str) -> fmt::Result
+    where
+        D: fmt::Write,
+    {
+        let line = self.line;
+        let skipped = line < self.skip_lines;
+        if skipped || self.predicate.as_mut().is_some_and(|p| !p(line, text)) {
+            self.action = LineAction::Keep;
+            self.needs_indent = false;
+            return Ok(());
+        }
+
+        let options = self.options.unwrap_or_default();
+        let cacheable = !matches!(self.format, Format::Filter { .. });
+        if self.cache_gutter && cacheable && line > 0 {
+            if self.gutter_cache.is_none() {
+                let mut buffer = StackBuffer::new();
+                match self.format.insert_line(line, text, &options, &mut buffer) {
+                    Ok(_) => self.gutter_cache = Some(buffer),
+                    Err(_) => self.cache_gutter = false,
+                }
+            }
+
+            if let Some(buffer) = self.gutter_cache {
+                self.write_gutter(buffer.as_str())?;
+                self.action = LineAction::Keep;
+                self.needs_indent = false;
+                return Ok(());
+            }
+        }
+
+        self.action = if self.atomic_gutter || self.stylize.is_some() {
+            let mut buffer = StackBuffer::new();
+            let action = self
+                .format
+                .insert_line(self.line, text, &options, &mut buffer)?;
+            self.write_gutter(buffer.as_str())?;
+            action
+        } else {
+            self.format
+                .insert_line(self.line, text, &options, &mut self.inner)?
+        };
+
+        self.needs_indent = false;
+        Ok(())
+    }
+
+    /// Writes indentation that has already been rendered, styling it if requested
+    fn write_gutter(&mut self, gutter: &str) -> fmt::Result
+    where
+        D: fmt::Write,
+    {
+        match &mut self.stylize {
+            Some(stylize) if self.color && !gutter.is_empty() => stylize(gutter, &mut self.inner),
+            _ => self.inner.write_str(gutter),
+        }
+    }
+
+    /// Writes a newline that was held back for `TrailingNewline::Drop`
+    fn flush_newline(&mut self) -> fmt::Result
+    where
+        D: fmt::Write,
+    {
+        if self.pending_newline {
+            self.pending_newline = false;
+            self.inner.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a piece of a line that contains no newlines, indenting it first if it starts the
+    /// line
+    ///
+    /// `last` is true for the piece at the end of a call to `write_str`, which may be continued
+    /// by the next call.
+    fn segment(&mut self, mut text: &str, last: bool) -> fmt::Result
+    where
+        D: fmt::Write,
+    {
+        let skipped = self.line < self.skip_lines;
+        if let (true, false, Some(prefix)) = (self.needs_indent, skipped, self.collapse_prefix) {
+            text = text.strip_prefix(prefix).unwrap_or(text);
+        }
+
+        if self.eager && self.needs_indent && !self.pending_newline {
+            self.indent("")?;
+        }
+
+        if self.needs_indent {
+            // Don't render the line unless its actually got text on it, or it is a blank line
+            // that empty line indentation applies to
+            if text.is_empty() && (self.skip_empty || last) {
+                return Ok(());
+            }
+
+            self.flush_newline()?;
+            self.indent(text)?;
+        }
+
+        if !text.is_empty() && self.action == LineAction::Keep {
+            self.flush_newline()?;
+            self.inner.write_str(text)?;
+        }
+
+        Ok(())
+    }
+
+    /// Ends the current line, after which the next line needs indenting
+    fn line_break(&mut self) -> fmt::Result
+    where
+        D: fmt::Write,
+    {
+        if mem::take(&mut self.action) != LineAction::Skip {
+            self.flush_newline()?;
+            if self.trailing_newline == TrailingNewline::Drop {
+                self.pending_newline = true;
+            } else {
+                self.inner.write_char('\n')?;
+            }
+        }
+
+        self.needs_indent = true;
+        self.line += 1;
+        Ok(())
+    }
 }
 
 impl<T> fmt::Write for Indented<'_, T>
 where
     T: fmt::Write + ?Sized,
 {
-    fn write_str(&mut self, s: &str) -> fmt::Result {
-        for (ind, line) in s.split('\n').enumerate() {
-            if ind > 0 {
-                self.inner.write_char('\n')?;
-                self.needs_indent = true;
-            }
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        if let Format::None = self.format {
+            return self.inner.write_str(s);
+        }
 
-            if self.needs_indent {
-                // Don't render the line unless its actually got text on it
-                if line.is_empty() {
-                    continue;
-                }
+        if self.strip_bom && !s.is_empty() {
+            self.strip_bom = false;
+            s = s.strip_prefix('\u{feff}').unwrap_or(s);
+        }
 
-                self.format.insert_indentation(ind, &mut self.inner)?;
-                self.needs_indent = false;
+        loop {
+            match s.find('\n') {
+                Some(end) => {
+                    self.segment(&s[..end], false)?;
+                    self.line_break()?;
+                    s = &s[end + 1..];
+                }
+                None => return self.segment(s, true),
             }
-
-            self.inner.write_fmt(format_args!("{}", line))?;
         }
+    }
+}
 
-        Ok(())
+/// The continuation lines of `Format::Numbered`, long enough for the widest possible gutter
+const NUMBERED_PADDING: &str = "                      ";
+
+/// Returns the number of decimal digits in `n`
+fn digits(mut n: usize) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+/// Writes the gutter of a numbered format before line `line`: the number on the first line, and
+/// blanks as wide as it on the rest
+fn write_numbered(line: usize, ind: usize, width: usize, f: &mut dyn fmt::Write) -> fmt::Result {
+    if line == 0 {
+        write_number(ind, width, f)
+    } else {
+        f.write_str(&NUMBERED_PADDING[..width + 2])
     }
 }
 
+/// Writes `ind` right aligned in `width` columns followed by `: `, like `{: >width$}: `
+fn write_number(ind: usize, width: usize, f: &mut dyn fmt::Write) -> fmt::Result {
+    write_padded(ind, 10, width, b' ', ": ", f)
+}
+
+/// Writes `n` in base `radix` with lowercase digits, padded on the left to `width` columns with
+/// `fill` and followed by `suffix`, all in a single write
+///
+/// The digits are rendered by hand so that the numbered format does not need to go through the
+/// formatting machinery at all. `width` is capped at 64 and `suffix` at 8 bytes.
+pub(crate) fn write_padded(
+    mut n: usize,
+    radix: usize,
+    width: usize,
+    fill: u8,
+    suffix: &str,
+    f: &mut dyn fmt::Write,
+) -> fmt::Result {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let mut buf = [fill; 72];
+    let suffix = &suffix.as_bytes()[..suffix.len().min(8)];
+    let mut start = buf.len() - suffix.len();
+    buf[start..].copy_from_slice(suffix);
+    let digits_end = start;
+
+    loop {
+        start -= 1;
+        buf[start] = DIGITS[n % radix];
+        n /= radix;
+
+        if n == 0 {
+            break;
+        }
+    }
+
+    let start = start.min(digits_end - width.min(64));
+    f.write_str(core::str::from_utf8(&buf[start..]).map_err(|_| fmt::Error)?)
+}
+
 /// Helper function for creating a default indenter
 pub fn indented<D: ?Sized>(f: &mut D) -> Indented<'_, D> {
     Indented {
         inner: f,
         needs_indent: true,
-        format: Format::Uniform {
-            indentation: "    ",
-        },
+        skip_empty: true,
+        line: 0,
+        strip_bom: false,
+        eager: false,
+        atomic_gutter: false,
+        cache_gutter: false,
+        gutter_cache: None,
+        trailing_newline: TrailingNewline::Keep,
+        pending_newline: false,
+        options: None,
+        action: LineAction::Keep,
+        skip_lines: 0,
+        collapse_prefix: None,
+        stylize: None,
+        color: true,
+        predicate: None,
+        format: Format::DEFAULT,
     }
 }
 
+/// Helper function for creating an indenter that matches the standard library's `{:#?}` output
+///
+/// Fields printed by the `debug_*` builders on `fmt::Formatter` are indented by four spaces on
+/// every line, including blank ones. This indenter follows the same rules, so output written
+/// through it lines up exactly with fields printed via `{:#?}`, and unlike `indented` it ignores
+/// the `default-*` features.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::pad_adapter;
+///
+/// let mut output = String::new();
+/// write!(pad_adapter(&mut output), "a\n\nb\n").unwrap();
+///
+/// assert_eq!(output, "    a\n    \n    b\n");
+/// ```
+pub fn pad_adapter<D: ?Sized>(f: &mut D) -> Indented<'_, D> {
+    indented(f).with_str("    ").skip_empty(false)
+}
+
 /// Helper struct for efficiently dedent and indent multi line display implementations
 ///
 /// # Explanation
@@ -247,13 +1308,28 @@ pub fn indented<D: ?Sized>(f: &mut D) -> Indented<'_, D> {
 #[allow(missing_debug_implementations)]
 pub struct CodeFormatter<'a, T> {
     f: &'a mut T,
-    level: u32,
+    level: Indent,
     indentation: String,
+    preserve_whitespace: bool,
+    needs_indent: bool,
 }
 
 #[cfg(feature = "std")]
 impl<'a, T: fmt::Write> fmt::Write for CodeFormatter<'a, T> {
     fn write_str(&mut self, input: &str) -> fmt::Result {
+        if self.preserve_whitespace {
+            let indentation = Indentation::new(self.level, &self.indentation);
+            let mut inserter = |_, f: &mut dyn fmt::Write| write!(f, "{}", indentation);
+            let mut indented = indented(&mut *self.f).with_format(Format::Custom {
+                inserter: &mut inserter,
+            });
+
+            indented.needs_indent = self.needs_indent;
+            let result = indented.write_str(input);
+            self.needs_indent = indented.needs_indent;
+            return result;
+        }
+
         let input = match input.chars().next() {
             Some('\n') => &input[1..],
             _ => return self.f.write_str(input),
@@ -270,7 +1346,7 @@ impl<'a, T: fmt::Write> fmt::Write for CodeFormatter<'a, T> {
 
         for line in input.split('\n') {
             if line.len().saturating_sub(min) > 0 {
-                for _ in 0..self.level {
+                for _ in 0..self.level.level() {
                     self.f.write_str(&self.indentation)?;
                 }
             }
@@ -278,7 +1354,7 @@ impl<'a, T: fmt::Write> fmt::Write for CodeFormatter<'a, T> {
             if line.len() >= min {
                 self.f.write_str(&line[min..])?;
             } else {
-                self.f.write_str(&line)?;
+                self.f.write_str(line)?;
             }
             self.f.write_char('\n')?;
         }
@@ -298,24 +1374,35 @@ impl<'a, T: fmt::Write> CodeFormatter<'a, T> {
     pub fn new<S: Into<String>>(f: &'a mut T, indentation: S) -> Self {
         Self {
             f,
-            level: 0,
+            level: Indent::ZERO,
             indentation: indentation.into(),
+            preserve_whitespace: false,
+            needs_indent: true,
         }
     }
 
+    /// Only prepend indentation to each line, instead of dedenting and trimming the input first
+    ///
+    /// Use this for content that is already indented the way it should be, such as a pretty
+    /// printed JSON value, whose leading whitespace would otherwise be treated as part of the
+    /// indentation of the source code it was written in.
+    pub fn set_preserve_whitespace(&mut self, preserve: bool) {
+        self.preserve_whitespace = preserve;
+    }
+
     /// Set the indentation level to a specific value
     pub fn set_level(&mut self, level: u32) {
-        self.level = level;
+        self.level = Indent(level as usize);
     }
 
     /// Increase the indentation level by `inc`
     pub fn indent(&mut self, inc: u32) {
-        self.level = self.level.saturating_add(inc);
+        self.level += inc as usize;
     }
 
     /// Decrease the indentation level by `inc`
     pub fn dedent(&mut self, inc: u32) {
-        self.level = self.level.saturating_sub(inc);
+        self.level -= inc as usize;
     }
 }
 
@@ -352,7 +1439,7 @@ mod tests {
     #[test]
     fn no_digits() {
         let input = "verify\nthis";
-        let expected = "    verify\n    this";
+        let expected = alloc::format!("{0}verify\n{0}this", DEFAULT_INDENTATION);
         let mut output = String::new();
 
         indented(&mut output).write_str(input).unwrap();
@@ -377,7 +1464,7 @@ mod tests {
     #[test]
     fn dyn_write() {
         let input = "verify\nthis";
-        let expected = "    verify\n    this";
+        let expected = alloc::format!("{0}verify\n{0}this", DEFAULT_INDENTATION);
         let mut output = String::new();
         let writer: &mut dyn core::fmt::Write = &mut output;
 
@@ -438,6 +1525,42 @@ mod tests {
         assert_eq!(expected, output);
     }
 
+    #[test]
+    fn pad_adapter_matches_std() {
+        struct Raw(&'static str);
+
+        impl fmt::Debug for Raw {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.0)
+            }
+        }
+
+        struct Manual(&'static str);
+
+        impl fmt::Debug for Manual {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("Std {\n")?;
+                writeln!(pad_adapter(f), "field: {:#?},", Raw(self.0))?;
+                f.write_str("}")
+            }
+        }
+
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct Std {
+            field: Raw,
+        }
+
+        for input in &["a", "a\n", "a\n\nb", "\n", "a\n\n\n"] {
+            let std = Std { field: Raw(input) };
+
+            assert_eq!(
+                alloc::format!("{:#?}", Manual(input)),
+                alloc::format!("{:#?}", std)
+            );
+        }
+    }
+
     #[test]
     fn several_interpolations() {
         let input = "verify\nthis\n";
@@ -448,6 +1571,304 @@ mod tests {
 
         assert_eq!(expected, output);
     }
+
+    /// Writes `input` in pseudo-randomly sized pieces, split at character boundaries
+    fn write_chunked(w: &mut dyn fmt::Write, input: &str, seed: u64) -> fmt::Result {
+        let mut state = seed | 1;
+        let mut rest = input;
+
+        while !rest.is_empty() {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            let mut at = (state % 4) as usize;
+            while at < rest.len() && !rest.is_char_boundary(at) {
+                at += 1;
+            }
+            let (chunk, tail) = rest.split_at(at.min(rest.len()));
+            w.write_str(chunk)?;
+            rest = tail;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn chunk_split_invariance() {
+        let inputs = [
+            "",
+            "a",
+            "\n",
+            "one\ntwo\n",
+            "\n\nleading and trailing\n\n",
+            "a\n\n\nb\r\nc",
+            "ünïcödé\n→ arrows ←\n\n",
+        ];
+
+        for input in &inputs {
+            for seed in 0..64 {
+                let mut whole = String::new();
+                let mut chunked = String::new();
+
+                indented(&mut whole).ind(3).write_str(input).unwrap();
+                write_chunked(&mut indented(&mut chunked).ind(3), input, seed).unwrap();
+                assert_eq!(whole, chunked, "numbered, input {:?} seed {}", input, seed);
+
+                let (mut whole, mut chunked) = (String::new(), String::new());
+                pad_adapter(&mut whole).write_str(input).unwrap();
+                write_chunked(&mut pad_adapter(&mut chunked), input, seed).unwrap();
+                assert_eq!(
+                    whole, chunked,
+                    "pad adapter, input {:?} seed {}",
+                    input, seed
+                );
+
+                let (mut whole, mut chunked) = (String::new(), String::new());
+                let mut numbers = |line: usize, f: &mut dyn fmt::Write| write!(f, "{}| ", line);
+                indented(&mut whole)
+                    .with_format(Format::Custom {
+                        inserter: &mut numbers,
+                    })
+                    .write_str(input)
+                    .unwrap();
+                write_chunked(
+                    &mut indented(&mut chunked).with_format(Format::Custom {
+                        inserter: &mut numbers,
+                    }),
+                    input,
+                    seed,
+                )
+                .unwrap();
+                assert_eq!(whole, chunked, "custom, input {:?} seed {}", input, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn numbered_blank_lines_are_empty() {
+        let input = "verify\n\nthis\n";
+        let expected = "   2: verify\n\n      this\n";
+        let mut output = String::new();
+
+        indented(&mut output).ind(2).write_str(input).unwrap();
+
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn numbered_matches_format_args() {
+        for &ind in &[0, 7, 42, 999, 1000, 9999, 10000, 123_456, usize::MAX] {
+            let mut output = String::new();
+            write_number(ind, 4, &mut output).unwrap();
+
+            assert_eq!(output, alloc::format!("{: >4}: ", ind));
+        }
+    }
+
+    #[test]
+    fn strip_bom() {
+        let mut output = String::new();
+        let mut indented = indented(&mut output).with_str("    ").strip_bom(true);
+
+        indented.write_str("").unwrap();
+        indented.write_str("\u{feff}a\n").unwrap();
+        indented.write_str("\u{feff}b").unwrap();
+
+        assert_eq!(output, "    a\n    \u{feff}b");
+    }
+
+    #[test]
+    fn trailing_newline_policies() {
+        for &(policy, expected) in &[
+            (TrailingNewline::Keep, "    a\n\n    b\n"),
+            (TrailingNewline::Drop, "    a\n\n    b"),
+            (TrailingNewline::Indent, "    a\n\n    b\n    "),
+        ] {
+            for seed in 0..16 {
+                let mut output = String::new();
+                let mut indented = indented(&mut output)
+                    .with_str("    ")
+                    .trailing_newline(policy);
+                write_chunked(&mut indented, "a\n\nb\n", seed).unwrap();
+                indented.finish().unwrap();
+
+                assert_eq!(output, expected, "{:?}", policy);
+            }
+        }
+    }
+
+    #[test]
+    fn eager_prefix() {
+        let mut output = String::new();
+        let mut indented = indented(&mut output).with_str("| ").eager(true);
+
+        indented.write_str("a\n").unwrap();
+        indented.inner.write_str("<b>").unwrap();
+        indented.write_str("b\n\nc").unwrap();
+
+        assert_eq!(output, "| a\n| <b>b\n| \n| c");
+    }
+
+    #[test]
+    fn atomic_gutter() {
+        let fail = core::cell::Cell::new(false);
+        let mut inserter = |_, f: &mut dyn fmt::Write| {
+            f.write_str("partial ")?;
+            if fail.get() {
+                return Err(fmt::Error);
+            }
+            f.write_str("gutter: ")
+        };
+        let mut output = String::new();
+        let mut indented = indented(&mut output)
+            .with_format(Format::Custom {
+                inserter: &mut inserter,
+            })
+            .atomic_gutter(true);
+
+        indented.write_str("a\n").unwrap();
+        fail.set(true);
+        assert!(indented.write_str("b").is_err());
+
+        assert_eq!(output, "partial gutter: a\n");
+    }
+
+    #[test]
+    fn started_state() {
+        let mut output = String::new();
+        let mut indented = indented(&mut output).with_str("> ");
+        assert!(!indented.started());
+
+        indented.write_str("a").unwrap();
+        assert!(indented.started());
+
+        indented.write_str("\n").unwrap();
+        assert!(!indented.started());
+
+        indented.set_started(true);
+        indented.write_str("b").unwrap();
+        indented.set_started(false);
+        indented.write_str("c").unwrap();
+
+        assert_eq!(output, "> a\nb> c");
+    }
+
+    #[test]
+    fn filter_lines_across_chunks() {
+        let mut filter = |line: usize, text: &str, f: &mut dyn fmt::Write| match text {
+            "skip" => Ok(LineAction::Skip),
+            "replace" => write!(f, "{}: replaced", line).map(|_| LineAction::Replaced),
+            _ => f.write_str("> ").map(|_| LineAction::Keep),
+        };
+
+        let mut output = String::new();
+        let mut indented = indented(&mut output).with_format(Format::Filter {
+            filter: &mut filter,
+        });
+        indented.write_str("a\nskip").unwrap();
+        indented.write_str(" more\nreplace").unwrap();
+        indented.write_str(" more\nb\n").unwrap();
+
+        assert_eq!(output, "> a\n2: replaced\n> b\n");
+    }
+
+    #[test]
+    fn indent_if_predicate() {
+        let mut odd = |line: usize, _: &str| line % 2 == 1;
+
+        let mut output = String::new();
+        let mut indented = indented(&mut output).with_str("> ").indent_if(&mut odd);
+        indented.write_str("a\nb\n").unwrap();
+        indented.write_str("c\nd").unwrap();
+
+        assert_eq!(output, "a\n> b\nc\n> d");
+    }
+
+    #[test]
+    fn skip_lines_across_chunks() {
+        let mut output = String::new();
+        let mut indented = indented(&mut output).with_str("> ").skip_lines(1);
+        indented.write_str("header").unwrap();
+        indented.write_str(" continued\n").unwrap();
+        indented.write_str("body\n").unwrap();
+
+        assert_eq!(output, "header continued\n> body\n");
+    }
+
+    #[test]
+    fn numbered_max_width() {
+        let mut output = String::new();
+        write!(indented(&mut output).ind_max(3, usize::MAX), "a\nb").unwrap();
+
+        let gutter = digits(usize::MAX) + 2;
+        assert_eq!(output.find('a'), Some(gutter));
+        assert_eq!(
+            output.rfind('b'),
+            Some(output.find('\n').unwrap() + 1 + gutter)
+        );
+    }
+
+    #[test]
+    fn cache_gutter_overflow() {
+        let mut calls = 0;
+        let mut inserter = |_, f: &mut dyn fmt::Write| {
+            calls += 1;
+            write!(f, "{}", Indentation::new(Indent(40), "  "))
+        };
+
+        let mut output = String::new();
+        let mut indented = indented(&mut output)
+            .with_format(Format::Custom {
+                inserter: &mut inserter,
+            })
+            .cache_gutter(true);
+        write!(indented, "a\nb\nc").unwrap();
+
+        assert_eq!(output.lines().count(), 3);
+        assert!(output.lines().all(|line| line.len() == 81));
+        assert_eq!(calls, 4);
+    }
+
+    #[test]
+    fn format_none_passes_through() {
+        let mut output = String::new();
+        let mut indented = indented(&mut output)
+            .with_format(Format::None)
+            .trailing_newline(TrailingNewline::Drop);
+        indented.write_str("a\n\nb\n").unwrap();
+        indented.finish().unwrap();
+
+        assert_eq!(output, "a\n\nb\n");
+    }
+
+    #[test]
+    fn stylize_cached_gutter() {
+        let mut bracket = |gutter: &str, f: &mut dyn fmt::Write| write!(f, "[{}]", gutter);
+
+        let mut output = String::new();
+        let mut indented = indented(&mut output)
+            .ind(1)
+            .cache_gutter(true)
+            .stylize_gutter(&mut bracket);
+        write!(indented, "a\nb\nc").unwrap();
+
+        assert_eq!(output, "[   1: ]a\n[      ]b\n[      ]c");
+    }
+
+    #[test]
+    fn collapse_prefix_blank_and_skipped_lines() {
+        let mut output = String::new();
+        let mut indented = indented(&mut output)
+            .with_str("> ")
+            .skip_lines(1)
+            .collapse_prefix("  ");
+        indented.write_str("  header\n  a\n").unwrap();
+        indented.write_str("  \n  b").unwrap();
+
+        assert_eq!(output, "  header\n> a\n\n> b");
+    }
 }
 
 #[cfg(all(test, feature = "std"))]
@@ -539,4 +1960,15 @@ mod tests_std {
         writeln!(f).unwrap();
         assert_eq!(s, "\n");
     }
+
+    #[test]
+    fn preserve_whitespace() {
+        let mut s = String::new();
+        let mut f = CodeFormatter::new(&mut s, "  ");
+        f.set_preserve_whitespace(true);
+        f.indent(1);
+        write!(f, "\n{{\n  \"a\": [\n    1\n  ]\n}}").unwrap();
+        writeln!(f, " ").unwrap();
+        assert_eq!(s, "\n  {\n    \"a\": [\n      1\n    ]\n  } \n");
+    }
 }
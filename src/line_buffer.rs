@@ -0,0 +1,85 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A writer that keeps every line of its output as a separate `String`
+///
+/// # Explanation
+///
+/// Pagination, test assertions and terminal UIs address output line by line. Writing through an
+/// `Indented` into a `LineBuffer` gives the indented lines one by one, without newlines, instead
+/// of a single string that has to be split again. A line is completed by a newline, and the line
+/// being written after the last newline is available from `partial` until then. This is behind
+/// the `alloc` feature.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, LineBuffer};
+///
+/// let mut lines = LineBuffer::new();
+/// writeln!(indented(&mut lines).ind(1), "first\nsecond")?;
+///
+/// assert_eq!(lines.lines(), ["   1: first", "      second"]);
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineBuffer {
+    lines: Vec<String>,
+    partial: String,
+}
+
+impl LineBuffer {
+    /// Construct an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the lines completed so far
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Returns the line written after the last newline, which is empty if nothing was
+    pub fn partial(&self) -> &str {
+        &self.partial
+    }
+
+    /// Returns every line, including the partial line if it is not empty
+    pub fn into_lines(mut self) -> Vec<String> {
+        if !self.partial.is_empty() {
+            self.lines.push(self.partial);
+        }
+
+        self.lines
+    }
+}
+
+impl fmt::Write for LineBuffer {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        while let Some(end) = s.find('\n') {
+            self.partial.push_str(&s[..end]);
+            self.lines.push(core::mem::take(&mut self.partial));
+            s = &s[end + 1..];
+        }
+
+        self.partial.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn partial_lines() {
+        let mut lines = LineBuffer::new();
+        lines.write_str("a\n\nb").unwrap();
+        assert_eq!(lines.lines(), ["a", ""]);
+        assert_eq!(lines.partial(), "b");
+
+        lines.write_str("c").unwrap();
+        assert_eq!(lines.into_lines(), ["a", "", "bc"]);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::Format;
+use core::cell::Cell;
+use core::fmt;
+use core::iter::Enumerate;
+use core::str::SplitTerminator;
+
+/// Helper struct for rendering indented output one line at a time
+///
+/// # Explanation
+///
+/// `Indented` only ever sees a stream of text, which is a poor fit for consumers that need to
+/// address output lines individually, for example to paginate or highlight them. `IndentLines`
+/// splits its input into lines up front and hands out one `Display` value per line. Each of them
+/// renders the same gutter that `Indented` would have inserted for that line, followed by the
+/// line's text without its newline.
+///
+/// ```rust
+/// use indenter::{indent_lines, Format};
+///
+/// let lines = indent_lines("verify\nthis\n", Format::Numbered { ind: 1 });
+/// let mut iter = lines.iter();
+///
+/// assert_eq!(iter.next().unwrap().to_string(), "   1: verify");
+/// assert_eq!(iter.next().unwrap().to_string(), "      this");
+/// assert!(iter.next().is_none());
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct IndentLines<'s, 'a> {
+    input: &'s str,
+    format: Cell<Option<Format<'a>>>,
+}
+
+/// Helper function for splitting `input` into lines indented with `format`
+pub fn indent_lines<'s, 'a>(input: &'s str, format: Format<'a>) -> IndentLines<'s, 'a> {
+    IndentLines {
+        input,
+        format: Cell::new(Some(format)),
+    }
+}
+
+impl<'s, 'a> IndentLines<'s, 'a> {
+    /// Iterate over the indented lines of the input
+    ///
+    /// A trailing newline does not produce an empty final line.
+    pub fn iter(&self) -> IndentLinesIter<'_, 's, 'a> {
+        IndentLinesIter {
+            lines: self.input.split_terminator('\n').enumerate(),
+            format: &self.format,
+        }
+    }
+}
+
+impl<'l, 's, 'a> IntoIterator for &'l IndentLines<'s, 'a> {
+    type Item = IndentedLine<'l, 's, 'a>;
+    type IntoIter = IndentLinesIter<'l, 's, 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the lines of an `IndentLines`
+#[allow(missing_debug_implementations)]
+pub struct IndentLinesIter<'l, 's, 'a> {
+    lines: Enumerate<SplitTerminator<'s, char>>,
+    format: &'l Cell<Option<Format<'a>>>,
+}
+
+impl<'l, 's, 'a> Iterator for IndentLinesIter<'l, 's, 'a> {
+    type Item = IndentedLine<'l, 's, 'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (number, text) = self.lines.next()?;
+
+        Some(IndentedLine {
+            number,
+            text,
+            format: self.format,
+        })
+    }
+}
+
+/// A single line of indented output produced by `IndentLines`
+#[allow(missing_debug_implementations)]
+pub struct IndentedLine<'l, 's, 'a> {
+    number: usize,
+    text: &'s str,
+    format: &'l Cell<Option<Format<'a>>>,
+}
+
+impl<'s> IndentedLine<'_, 's, '_> {
+    /// The line number within the output, starting from 0
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// The text of the line without its gutter
+    pub fn text(&self) -> &'s str {
+        self.text
+    }
+}
+
+impl fmt::Display for IndentedLine<'_, '_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Don't render the gutter unless the line actually has text on it, like `Indented`
+        if !self.text.is_empty() {
+            let mut format = self.format.take().ok_or(fmt::Error)?;
+            let result = format.render_gutter(self.number, f);
+            self.format.set(Some(format));
+            result?;
+        }
+
+        f.write_str(self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use crate::indented;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    #[test]
+    fn matches_indented() {
+        let input = "one\n\ntwo\nthree";
+        let mut expected = String::new();
+        indented(&mut expected).ind(7).write_str(input).unwrap();
+
+        let lines = indent_lines(input, Format::Numbered { ind: 7 });
+        let rendered: Vec<_> = lines.iter().map(|line| line.to_string()).collect();
+
+        assert_eq!(rendered.join("\n"), expected);
+    }
+
+    #[test]
+    fn render_out_of_order() {
+        let mut inserter = |line, f: &mut dyn Write| write!(f, "{}| ", line);
+        let lines = indent_lines(
+            "a\nb",
+            Format::Custom {
+                inserter: &mut inserter,
+            },
+        );
+        let items: Vec<_> = lines.iter().collect();
+
+        assert_eq!(items[1].to_string(), "1| b");
+        assert_eq!(items[0].to_string(), "0| a");
+        assert_eq!(items[1].text(), "b");
+    }
+}
@@ -0,0 +1,158 @@
+use core::fmt::{self, Write};
+
+/// Helper struct for writing a value as a Rust string literal
+///
+/// # Explanation
+///
+/// Golden file tests and code generators often need some output pasted into Rust source as a
+/// string constant. This type displays a value as a string literal that evaluates back to exactly
+/// the text the value displays as. By default the literal is quoted and escaped. With `raw`, which
+/// needs the `alloc` feature, it is a raw string literal with just enough `#`s around it, keeping
+/// line breaks and quotes readable, unless the text has a carriage return, which raw string
+/// literals can't contain.
+///
+/// ```rust
+/// use indenter::{indent_display, rust_string};
+///
+/// let report = indent_display("error:\n\"quoted\"", "  ");
+///
+/// assert_eq!(
+///     rust_string(report).to_string(),
+///     r#""  error:\n  \"quoted\"""#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RustString<T> {
+    value: T,
+    #[cfg(feature = "alloc")]
+    raw: bool,
+}
+
+/// Create a `RustString` that displays `value` as a Rust string literal
+pub fn rust_string<T: fmt::Display>(value: T) -> RustString<T> {
+    RustString {
+        value,
+        #[cfg(feature = "alloc")]
+        raw: false,
+    }
+}
+
+impl<T> RustString<T> {
+    /// Write a raw string literal instead of escaping the value
+    ///
+    /// ```rust
+    /// use indenter::rust_string;
+    ///
+    /// assert_eq!(
+    ///     rust_string("say \"#hi\"\nbye").raw().to_string(),
+    ///     "r##\"say \"#hi\"\nbye\"##"
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn raw(mut self) -> Self {
+        self.raw = true;
+        self
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for RustString<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        if self.raw {
+            return write_raw(f, &self.value);
+        }
+
+        f.write_char('"')?;
+        write!(RustEscape { inner: &mut *f }, "{}", self.value)?;
+        f.write_char('"')
+    }
+}
+
+/// Writes `value` as a raw string literal with as few `#`s as its contents allow
+///
+/// Raw string literals can't contain a carriage return, so text with one is escaped instead.
+#[cfg(feature = "alloc")]
+fn write_raw(f: &mut fmt::Formatter<'_>, value: &dyn fmt::Display) -> fmt::Result {
+    let text = alloc::string::ToString::to_string(value);
+    if text.contains('\r') {
+        f.write_char('"')?;
+        RustEscape { inner: &mut *f }.write_str(&text)?;
+        return f.write_char('"');
+    }
+
+    // The literal ends at the first quote followed by as many `#`s as it was opened with
+    let hashes = text
+        .split('"')
+        .skip(1)
+        .map(|after| after.len() - after.trim_start_matches('#').len() + 1)
+        .max()
+        .unwrap_or(0);
+
+    f.write_char('r')?;
+    for _ in 0..hashes {
+        f.write_char('#')?;
+    }
+    write!(f, "\"{}\"", text)?;
+    for _ in 0..hashes {
+        f.write_char('#')?;
+    }
+
+    Ok(())
+}
+
+/// Escapes text for the inside of a Rust string literal
+struct RustEscape<'a, W: ?Sized> {
+    inner: &'a mut W,
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for RustEscape<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+        while let Some(pos) = rest.find(|c: char| c == '"' || c == '\\' || c.is_control()) {
+            let (plain, tail) = rest.split_at(pos);
+            self.inner.write_str(plain)?;
+
+            let c = tail.chars().next().unwrap_or_default();
+            match c {
+                '"' => self.inner.write_str("\\\"")?,
+                '\\' => self.inner.write_str("\\\\")?,
+                '\n' => self.inner.write_str("\\n")?,
+                '\r' => self.inner.write_str("\\r")?,
+                '\t' => self.inner.write_str("\\t")?,
+                '\0' => self.inner.write_str("\\0")?,
+                _ => write!(self.inner, "\\u{{{:x}}}", c as u32)?,
+            }
+            rest = &tail[c.len_utf8()..];
+        }
+
+        self.inner.write_str(rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn control_characters() {
+        assert_eq!(
+            rust_string("a\u{0}\u{1b}\\").to_string(),
+            r#""a\0\u{1b}\\""#
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn raw_without_quotes() {
+        assert_eq!(rust_string("plain").raw().to_string(), "r\"plain\"");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn raw_with_carriage_return() {
+        assert_eq!(rust_string("a\r\nb").raw().to_string(), r#""a\r\nb""#);
+    }
+}
@@ -0,0 +1,157 @@
+/// Write formatted data through an indenter using a static string as indentation
+///
+/// This constructs an `Indented` around the writer, forwards the format arguments, and evaluates
+/// to the resulting `fmt::Result`.
+///
+/// ```rust
+/// use indenter::indented;
+///
+/// let mut output = String::new();
+/// indented!(&mut output, "  ", "{}:\n{}", "key", "value").unwrap();
+///
+/// assert_eq!(output, "  key:\n  value");
+/// ```
+#[macro_export]
+macro_rules! indented {
+    ($f:expr, $indentation:expr, $($arg:tt)*) => {
+        ::core::fmt::Write::write_fmt(
+            &mut $crate::indented($f).with_str($indentation),
+            ::core::format_args!($($arg)*),
+        )
+    };
+}
+
+/// Write formatted data followed by a newline through an indenter
+///
+/// This is the `writeln!` counterpart of `indented!`.
+///
+/// ```rust
+/// use indenter::indented_writeln;
+///
+/// let mut output = String::new();
+/// indented_writeln!(&mut output, "  ", "{} = {}", "key", 1).unwrap();
+/// indented_writeln!(&mut output, "  ").unwrap();
+///
+/// assert_eq!(output, "  key = 1\n\n");
+/// ```
+#[macro_export]
+macro_rules! indented_writeln {
+    ($f:expr, $indentation:expr) => {
+        ::core::fmt::Write::write_char(&mut $crate::indented($f).with_str($indentation), '\n')
+    };
+    ($f:expr, $indentation:expr, $($arg:tt)*) => {{
+        let mut indented = $crate::indented($f).with_str($indentation);
+
+        ::core::fmt::Write::write_fmt(&mut indented, ::core::format_args!($($arg)*))
+            .and_then(|()| ::core::fmt::Write::write_char(&mut indented, '\n'))
+    }};
+}
+
+/// Write formatted data indented by a number of indentation units
+///
+/// Every line of the output is prefixed with the unit repeated `level` times, where `level` is an
+/// `Indent` or a `usize`. The unit defaults to `DEFAULT_INDENTATION` and can be overridden with
+/// `unit = "..."` after the level. This is intended for code generators, where it lets the
+/// emitting code mirror the structure of the generated code.
+///
+/// ```rust
+/// use indenter::{iwrite, iwriteln, DEFAULT_INDENTATION};
+///
+/// let mut output = String::new();
+/// iwriteln!(&mut output, 0, "fn main() {{").unwrap();
+/// iwriteln!(&mut output, 1, "let x = {};", 42).unwrap();
+/// iwriteln!(&mut output, 1, unit = "\t", "drop(x);").unwrap();
+/// iwrite!(&mut output, 0, "}}").unwrap();
+///
+/// assert_eq!(
+///     output,
+///     format!("fn main() {{\n{}let x = 42;\n\tdrop(x);\n}}", DEFAULT_INDENTATION)
+/// );
+/// ```
+#[macro_export]
+macro_rules! iwrite {
+    ($w:expr, $level:expr, unit = $unit:expr, $($arg:tt)*) => {{
+        let level: $crate::Indent = ::core::convert::Into::into($level);
+        let unit: &str = $unit;
+
+        ::core::fmt::Write::write_fmt(
+            &mut $crate::indented($w).with_format($crate::Format::Custom {
+                inserter: &mut |_, f: &mut dyn ::core::fmt::Write| {
+                    f.write_fmt(::core::format_args!("{}", $crate::Indentation::new(level, unit)))
+                },
+            }),
+            ::core::format_args!($($arg)*),
+        )
+    }};
+    ($w:expr, $level:expr, $($arg:tt)*) => {
+        $crate::iwrite!($w, $level, unit = $crate::DEFAULT_INDENTATION, $($arg)*)
+    };
+}
+
+/// Write formatted data followed by a newline, indented by a number of indentation units
+///
+/// This is the `writeln!` counterpart of `iwrite!`.
+#[macro_export]
+macro_rules! iwriteln {
+    ($w:expr, $level:expr) => {
+        $crate::iwrite!($w, $level, "\n")
+    };
+    ($w:expr, $level:expr, unit = $unit:expr, $($arg:tt)*) => {
+        $crate::iwrite!($w, $level, unit = $unit, "{}\n", ::core::format_args!($($arg)*))
+    };
+    ($w:expr, $level:expr, $($arg:tt)*) => {
+        $crate::iwrite!($w, $level, "{}\n", ::core::format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use crate::{Indent, DEFAULT_INDENTATION};
+    use alloc::string::String;
+    use core::fmt;
+
+    struct Pair(&'static str, &'static str);
+
+    impl fmt::Display for Pair {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            writeln!(f, "{}:", self.0)?;
+            indented!(f, "    ", "{}", self.1)
+        }
+    }
+
+    #[test]
+    fn in_display_impl() {
+        let mut output = String::new();
+        fmt::Write::write_fmt(&mut output, format_args!("{}", Pair("key", "a\nb"))).unwrap();
+
+        assert_eq!(output, "key:\n    a\n    b");
+    }
+
+    #[test]
+    fn writeln_trailing_newline() {
+        let mut output = String::new();
+        indented_writeln!(&mut output, "> ", "{}", "a\nb").unwrap();
+        indented_writeln!(&mut output, "> ", "c").unwrap();
+
+        assert_eq!(output, "> a\n> b\n> c\n");
+    }
+
+    #[test]
+    fn level_macros() {
+        let mut output = String::new();
+        iwriteln!(&mut output, 1, "if {} {{\n    return;\n}}", true).unwrap();
+        iwriteln!(&mut output, 0).unwrap();
+        iwriteln!(&mut output, 2, unit = "  ", "{}", "done").unwrap();
+        iwrite!(&mut output, Indent(1) + 1, unit = "  ", "deeper").unwrap();
+
+        assert_eq!(
+            output,
+            alloc::format!(
+                "{0}if true {{\n{0}    return;\n{0}}}\n\n    done\n    deeper",
+                DEFAULT_INDENTATION
+            )
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::buffer::StackBuffer;
+use crate::{indented, Format};
+use core::fmt::{self, Write};
+use core::mem;
+
+/// A writer for Markdown documents with nested lists, quotes and code blocks
+///
+/// # Explanation
+///
+/// Container blocks such as list items and blockquotes are written as closure scopes, each of
+/// which gets its own `MarkdownWriter` whose output is prefixed through an `Indented` with the
+/// marker of the container on its first line and the matching continuation indentation on the
+/// others. Nesting containers therefore nests their prefixes without the caller tracking them.
+/// Blocks are separated by blank lines, except inside list items where they are kept tight.
+///
+/// ```rust
+/// use indenter::MarkdownWriter;
+///
+/// let mut output = String::new();
+/// let mut w = MarkdownWriter::new(&mut output);
+/// w.paragraph("Steps:")?;
+/// w.ordered_list(|l| {
+///     l.item("Build")?;
+///     l.item_with(|w| {
+///         w.paragraph("Test")?;
+///         w.list(|l| l.item("unit"))?;
+///         w.code_block("sh", "cargo test")
+///     })
+/// })?;
+///
+/// assert_eq!(
+///     output,
+///     "Steps:\n\n1. Build\n2. Test\n   - unit\n   ```sh\n   cargo test\n   ```\n"
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct MarkdownWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    tight: bool,
+    started: bool,
+}
+
+/// The items of a list being written by a `MarkdownWriter`
+#[allow(missing_debug_implementations)]
+pub struct MarkdownList<'w, 'a> {
+    writer: &'w mut MarkdownWriter<'a>,
+    number: Option<usize>,
+}
+
+impl<'a> MarkdownWriter<'a> {
+    /// Construct a writer for a document written to `inner`
+    pub fn new(inner: &'a mut dyn fmt::Write) -> Self {
+        Self {
+            inner,
+            tight: false,
+            started: false,
+        }
+    }
+
+    /// Write a paragraph of text
+    pub fn paragraph(&mut self, text: impl fmt::Display) -> fmt::Result {
+        self.begin_block()?;
+        writeln!(self.inner, "{}", text)
+    }
+
+    /// Write a heading of the given `level`, from 1 to 6
+    pub fn heading(&mut self, level: usize, text: impl fmt::Display) -> fmt::Result {
+        self.begin_block()?;
+        for _ in 0..level.clamp(1, 6) {
+            self.inner.write_char('#')?;
+        }
+        writeln!(self.inner, " {}", text)
+    }
+
+    /// Write a fenced code block
+    ///
+    /// `code` is written as is, only the prefixes of the enclosing list items and quotes are added
+    /// to its lines. The fence is made longer than any run of backticks in `code`.
+    pub fn code_block(&mut self, lang: &str, code: &str) -> fmt::Result {
+        self.begin_block()?;
+
+        let longest = code
+            .split(|c: char| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or_default();
+        let fence = Fence(longest.max(2) + 1);
+
+        writeln!(self.inner, "{}{}", fence, lang)?;
+        self.inner.write_str(code)?;
+        if !code.is_empty() && !code.ends_with('\n') {
+            self.inner.write_char('\n')?;
+        }
+        writeln!(self.inner, "{}", fence)
+    }
+
+    /// Write a bullet list, whose items are written by `f`
+    pub fn list<F>(&mut self, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut MarkdownList<'_, 'a>) -> fmt::Result,
+    {
+        self.begin_block()?;
+        f(&mut MarkdownList {
+            writer: self,
+            number: None,
+        })
+    }
+
+    /// Write an ordered list numbered from 1, whose items are written by `f`
+    pub fn ordered_list<F>(&mut self, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut MarkdownList<'_, 'a>) -> fmt::Result,
+    {
+        self.begin_block()?;
+        f(&mut MarkdownList {
+            writer: self,
+            number: Some(1),
+        })
+    }
+
+    /// Write a blockquote, whose contents are written by `f`
+    pub fn quote<F>(&mut self, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut MarkdownWriter<'_>) -> fmt::Result,
+    {
+        self.begin_block()?;
+
+        let mut inserter = |_, f: &mut dyn Write| f.write_str("> ");
+        // Blank lines must keep their marker, otherwise they end the quote
+        let mut indented = indented(&mut *self.inner)
+            .with_format(Format::Custom {
+                inserter: &mut inserter,
+            })
+            .skip_empty(false);
+
+        f(&mut MarkdownWriter::new(&mut indented))
+    }
+
+    /// Write `term` followed by a definition, whose contents are written by `f`
+    ///
+    /// This uses the `: ` definition list syntax supported by many Markdown extensions.
+    pub fn definition<F>(&mut self, term: impl fmt::Display, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut MarkdownWriter<'_>) -> fmt::Result,
+    {
+        self.begin_block()?;
+        writeln!(self.inner, "{}", term)?;
+        self.nested(":   ", "    ", false, f)
+    }
+
+    /// Separates a new block from the previous one
+    fn begin_block(&mut self) -> fmt::Result {
+        if mem::replace(&mut self.started, true) && !self.tight {
+            self.inner.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs `f` with a writer prefixed by `marker` on its first line and `continuation` on others
+    fn nested<F>(&mut self, marker: &str, continuation: &str, tight: bool, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut MarkdownWriter<'_>) -> fmt::Result,
+    {
+        let mut first = true;
+        let mut inserter = |_, f: &mut dyn Write| {
+            f.write_str(if mem::replace(&mut first, false) {
+                marker
+            } else {
+                continuation
+            })
+        };
+        let mut indented = indented(&mut *self.inner).with_format(Format::Custom {
+            inserter: &mut inserter,
+        });
+
+        let mut writer = MarkdownWriter::new(&mut indented);
+        writer.tight = tight;
+        f(&mut writer)
+    }
+}
+
+impl<'a> MarkdownList<'_, 'a> {
+    /// Write an item consisting of a single paragraph
+    pub fn item(&mut self, text: impl fmt::Display) -> fmt::Result {
+        self.item_with(|w| w.paragraph(text))
+    }
+
+    /// Write an item, whose blocks are written by `f`
+    pub fn item_with<F>(&mut self, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut MarkdownWriter<'_>) -> fmt::Result,
+    {
+        match self.number {
+            None => self.writer.nested("- ", "  ", true, f),
+            Some(number) => {
+                self.number = Some(number + 1);
+
+                let mut marker = StackBuffer::new();
+                write!(marker, "{}. ", number)?;
+                let continuation = &SPACES[..marker.len()];
+                self.writer.nested(marker.as_str(), continuation, true, f)
+            }
+        }
+    }
+}
+
+/// Continuation indentation for ordered list markers, as long as the longest marker
+const SPACES: &str = "                        ";
+
+/// A run of backticks
+struct Fence(usize);
+
+impl fmt::Display for Fence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for _ in 0..self.0 {
+            f.write_char('`')?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn nested_quotes_and_lists() {
+        let mut output = String::new();
+        let mut w = MarkdownWriter::new(&mut output);
+        w.quote(|w| {
+            w.paragraph("a")?;
+            w.list(|l| {
+                l.item_with(|w| {
+                    w.paragraph("b")?;
+                    w.list(|l| l.item("c\nd"))
+                })
+            })
+        })
+        .unwrap();
+
+        assert_eq!(output, "> a\n> \n> - b\n>   - c\n>     d\n");
+    }
+
+    #[test]
+    fn definitions_and_fences() {
+        let mut output = String::new();
+        let mut w = MarkdownWriter::new(&mut output);
+        w.heading(2, "Options").unwrap();
+        w.definition("`--fence`", |w| {
+            w.paragraph("Example:")?;
+            w.code_block("", "```\nnested\n```\n")
+        })
+        .unwrap();
+
+        assert_eq!(
+            output,
+            "## Options\n\n`--fence`\n:   Example:\n\n    ````\n    ```\n    nested\n    ```\n    ````\n"
+        );
+    }
+}
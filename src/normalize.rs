@@ -0,0 +1,107 @@
+use core::fmt;
+
+/// Helper function for displaying `text` with its common indentation replaced by `prefix`
+///
+/// # Explanation
+///
+/// A string literal embedded in Rust source picks up the indentation of the code around it, so
+/// writing it as it is, or indenting it further, puts it at the wrong depth. This strips the
+/// leading whitespace common to every non-blank line of `text`, as `dedent` does, and writes
+/// `prefix` before every non-blank line instead, in one pass over the formatter without
+/// allocating. Lines that are only whitespace are written empty. As in a heredoc, a newline right
+/// at the start of `text` and whitespace after its last newline are dropped, so the literal can
+/// open and close on lines of its own.
+///
+/// ```rust
+/// use indenter::normalize_indent;
+///
+/// let script = "
+///         set -e
+///         if [ -n \"$CI\" ]; then
+///             make test
+///         fi
+///     ";
+///
+/// assert_eq!(
+///     normalize_indent(script, "  ").to_string(),
+///     "  set -e\n  if [ -n \"$CI\" ]; then\n      make test\n  fi\n"
+/// );
+/// ```
+pub fn normalize_indent<'a>(text: &'a str, prefix: &'a str) -> NormalizedIndent<'a> {
+    let text = text.strip_prefix('\n').unwrap_or(text);
+    let text = match text.rfind('\n') {
+        Some(end) if text[end + 1..].trim().is_empty() => &text[..=end],
+        _ => text,
+    };
+
+    NormalizedIndent { text, prefix }
+}
+
+/// The `Display` value returned by `normalize_indent`
+#[derive(Debug, Clone, Copy)]
+pub struct NormalizedIndent<'a> {
+    text: &'a str,
+    prefix: &'a str,
+}
+
+impl NormalizedIndent<'_> {
+    /// Returns the leading whitespace common to every non-blank line
+    fn common(&self) -> &str {
+        let mut common: Option<&str> = None;
+
+        for line in self.text.lines().filter(|line| !line.trim().is_empty()) {
+            let leading = &line[..line.len() - line.trim_start().len()];
+            common = Some(match common {
+                None => leading,
+                Some(common) => {
+                    let len = common
+                        .char_indices()
+                        .zip(leading.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map(|((i, _), _)| i)
+                        .unwrap_or_else(|| common.len().min(leading.len()));
+                    &common[..len]
+                }
+            });
+        }
+
+        common.unwrap_or_default()
+    }
+}
+
+impl fmt::Display for NormalizedIndent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let common = self.common().len();
+
+        for (ind, line) in self.text.split('\n').enumerate() {
+            if ind > 0 {
+                f.write_str("\n")?;
+            }
+
+            if !line.trim().is_empty() {
+                f.write_str(self.prefix)?;
+                f.write_str(&line[common..])?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn blank_lines_and_tabs() {
+        let text = "\t\ta\n\t\n\t\t\tb\r\n\t\tc";
+
+        assert_eq!(
+            normalize_indent(text, "> ").to_string(),
+            "> a\n\n> \tb\r\n> c"
+        );
+        assert_eq!(normalize_indent("\n  \n", "> ").to_string(), "\n");
+    }
+}
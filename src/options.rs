@@ -0,0 +1,145 @@
+use crate::{indented, Indented};
+use core::fmt;
+
+/// The formatting options of a `fmt::Formatter`, captured so they outlive borrowing it
+///
+/// # Explanation
+///
+/// Once a formatter is wrapped by an `Indented` it can no longer be asked for its options, and
+/// custom inserters only ever see it as a `dyn fmt::Write`. `indented_formatter` captures the
+/// options first, and passes them to every call of a `Format::CustomWithOptions` inserter, so it
+/// can take the width, precision and flags of the outer format string into account.
+///
+/// ```rust
+/// use core::fmt::{self, Write};
+/// use indenter::{indented_formatter, Format, FormatterOptions};
+///
+/// struct Padded<'a>(&'a str);
+///
+/// impl fmt::Display for Padded<'_> {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         let mut inserter = |_, options: &FormatterOptions, f: &mut dyn Write| {
+///             for _ in 0..options.width.unwrap_or(2) {
+///                 f.write_char(options.fill)?;
+///             }
+///             Ok(())
+///         };
+///         let format = Format::CustomWithOptions { inserter: &mut inserter };
+///
+///         write!(indented_formatter(f).with_format(format), "{}", self.0)
+///     }
+/// }
+///
+/// assert_eq!(format!("{}", Padded("a\nb")), "  a\n  b");
+/// assert_eq!(format!("{:.>4}", Padded("a\nb")), "....a\n....b");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatterOptions {
+    /// The requested minimum width
+    pub width: Option<usize>,
+    /// The requested precision
+    pub precision: Option<usize>,
+    /// The fill character used for padding
+    pub fill: char,
+    /// The requested alignment
+    pub align: Option<fmt::Alignment>,
+    /// Whether the `#` flag was given
+    pub alternate: bool,
+    /// Whether the `+` flag was given
+    pub sign_plus: bool,
+    /// Whether the `-` flag was given
+    pub sign_minus: bool,
+    /// Whether the `0` flag was given
+    pub sign_aware_zero_pad: bool,
+}
+
+impl Default for FormatterOptions {
+    /// The options of a plain `{}`
+    fn default() -> Self {
+        Self {
+            width: None,
+            precision: None,
+            fill: ' ',
+            align: None,
+            alternate: false,
+            sign_plus: false,
+            sign_minus: false,
+            sign_aware_zero_pad: false,
+        }
+    }
+}
+
+impl FormatterOptions {
+    /// Capture the options of `f`
+    pub fn of(f: &fmt::Formatter<'_>) -> Self {
+        Self {
+            width: f.width(),
+            precision: f.precision(),
+            fill: f.fill(),
+            align: f.align(),
+            alternate: f.alternate(),
+            sign_plus: f.sign_plus(),
+            sign_minus: f.sign_minus(),
+            sign_aware_zero_pad: f.sign_aware_zero_pad(),
+        }
+    }
+}
+
+/// Helper function for creating an indenter for a formatter that remembers its options
+///
+/// The options are passed to `Format::CustomWithOptions` inserters, and are available from
+/// `Indented::options`.
+pub fn indented_formatter<'a, 'b>(
+    f: &'a mut fmt::Formatter<'b>,
+) -> Indented<'a, fmt::Formatter<'b>> {
+    let options = FormatterOptions::of(f);
+    let mut indented = indented(f);
+    indented.options = Some(options);
+    indented
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use crate::Format;
+    use alloc::format;
+    use fmt::Write;
+
+    struct Options;
+
+    impl fmt::Display for Options {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let options = indented_formatter(f).options().copied().ok_or(fmt::Error)?;
+            write!(f, "{:?}", (options.width, options.precision, options.fill))
+        }
+    }
+
+    #[test]
+    fn captures_options() {
+        assert_eq!(format!("{}", Options), "(None, None, ' ')");
+        assert_eq!(format!("{:*^7.3}", Options), "(Some(7), Some(3), '*')");
+    }
+
+    struct Gutter;
+
+    impl fmt::Display for Gutter {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let mut inserter = |line, options: &FormatterOptions, f: &mut dyn fmt::Write| {
+                write!(f, "{:>1$}: ", line, options.width.unwrap_or(1))
+            };
+            let format = Format::CustomWithOptions {
+                inserter: &mut inserter,
+            };
+
+            write!(indented_formatter(f).with_format(format), "a\nb")
+        }
+    }
+
+    #[test]
+    fn inserters_see_options() {
+        assert_eq!(format!("{}", Gutter), "0: a\n1: b");
+        assert_eq!(format!("{:3}", Gutter), "  0: a\n  1: b");
+    }
+}
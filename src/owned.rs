@@ -0,0 +1,110 @@
+use crate::{Format, InserterSend};
+use alloc::boxed::Box;
+use core::fmt;
+
+/// A `Format` that owns its inserter, for storing a configured format in a long lived value
+///
+/// # Explanation
+///
+/// `Format::Custom` borrows its inserter, which ties the format to the scope the inserter lives
+/// in. An `OwnedFormat` boxes the inserter instead, so a report handler can be configured with a
+/// format once and keep it, then borrow it as a `Format` for each indenter it creates.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, OwnedFormat};
+///
+/// struct Handler {
+///     format: OwnedFormat,
+/// }
+///
+/// let mut handler = Handler {
+///     format: OwnedFormat::custom(|line, f| f.write_str(if line == 0 { "- " } else { "  " })),
+/// };
+///
+/// let mut output = String::new();
+/// writeln!(indented(&mut output).with_format(handler.format.as_format()), "a\nb")?;
+/// writeln!(indented(&mut output).with_format(handler.format.as_format()), "c")?;
+///
+/// assert_eq!(output, "- a\n  b\n- c\n");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub enum OwnedFormat {
+    /// Pass all output through unchanged, like `Format::None`
+    None,
+    /// Insert uniform indentation before every line, like `Format::Uniform`
+    Uniform {
+        /// The string to insert as indentation
+        indentation: &'static str,
+    },
+    /// Insert a number before the first line, like `Format::Numbered`
+    Numbered {
+        /// The index to insert before the first line of output
+        ind: usize,
+    },
+    /// Insert a number before the first line in a gutter sized for the largest index, like
+    /// `Format::NumberedMax`
+    NumberedMax {
+        /// The index to insert before the first line of output
+        ind: usize,
+        /// The largest index the gutter should have room for
+        max: usize,
+    },
+    /// A custom indenter which is executed after every newline, like `Format::Custom`
+    Custom {
+        /// The custom indenter
+        inserter: Box<InserterSend>,
+    },
+}
+
+impl OwnedFormat {
+    /// Construct an `OwnedFormat::Custom` from a closure
+    pub fn custom<F>(inserter: F) -> Self
+    where
+        F: FnMut(usize, &mut dyn fmt::Write) -> fmt::Result + Send + 'static,
+    {
+        OwnedFormat::Custom {
+            inserter: Box::new(inserter),
+        }
+    }
+
+    /// Borrow this format as a `Format` for use by an indenter
+    pub fn as_format(&mut self) -> Format<'_> {
+        match *self {
+            OwnedFormat::None => Format::None,
+            OwnedFormat::Uniform { indentation } => Format::Uniform { indentation },
+            OwnedFormat::Numbered { ind } => Format::Numbered { ind },
+            OwnedFormat::NumberedMax { ind, max } => Format::NumberedMax { ind, max },
+            OwnedFormat::Custom { ref mut inserter } => Format::Custom {
+                inserter: &mut **inserter,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indented;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn inserter_state_is_kept() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let mut count = 0;
+        let mut format = OwnedFormat::custom(move |_, f| {
+            count += 1;
+            write!(f, "{} ", count)
+        });
+        assert_send(&format);
+
+        let mut output = String::new();
+        writeln!(indented(&mut output).with_format(format.as_format()), "a").unwrap();
+        write!(indented(&mut output).with_format(format.as_format()), "b").unwrap();
+
+        assert_eq!(output, "1 a\n2 b");
+    }
+}
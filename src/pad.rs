@@ -0,0 +1,102 @@
+use crate::spaces::write_spaces;
+use crate::width::Column;
+use core::fmt;
+
+/// A writer that pads every line written through it with spaces to a fixed display width
+///
+/// # Explanation
+///
+/// Drawing a border on the right of some output, or keeping the panes of a terminal UI from
+/// shifting, needs every line to end in the same column. This adapter measures each line with
+/// `display_width` as it is written, and fills it with spaces up to `width` before the newline.
+/// Lines that are already as wide are left as they are. Wrapping it in `indented` pads the
+/// indented lines, indentation included. The last line isn't padded until `finish` is called,
+/// since more of it could still be written.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, padded};
+///
+/// let mut output = String::new();
+/// let mut padded = padded(&mut output, 6);
+/// write!(indented(&mut padded).with_str("> "), "a\n\nbcd")?;
+/// padded.finish()?;
+///
+/// assert_eq!(output, "> a   \n      \n> bcd ");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct Padded<'a, W: ?Sized> {
+    inner: &'a mut W,
+    width: usize,
+    column: Column,
+    /// Whether anything was written on the current line
+    started: bool,
+}
+
+/// Helper function for creating a writer that pads every line to `width` columns
+pub fn padded<W: ?Sized>(inner: &mut W, width: usize) -> Padded<'_, W> {
+    Padded {
+        inner,
+        width,
+        column: Column::default(),
+        started: false,
+    }
+}
+
+impl<W: fmt::Write + ?Sized> Padded<'_, W> {
+    /// Pad the last line, if anything was written on it
+    pub fn finish(&mut self) -> fmt::Result {
+        if self.started {
+            self.pad()?;
+        }
+
+        Ok(())
+    }
+
+    /// Fill the current line up to the width and start the next one
+    fn pad(&mut self) -> fmt::Result {
+        write_spaces(self.inner, self.width.saturating_sub(self.column.width))?;
+        self.column = Column::default();
+        self.started = false;
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Padded<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (ind, line) in s.split('\n').enumerate() {
+            if ind > 0 {
+                self.pad()?;
+                self.inner.write_char('\n')?;
+            }
+
+            if !line.is_empty() {
+                self.inner.write_str(line)?;
+                self.column.advance(line);
+                self.started = true;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::String;
+    use fmt::Write;
+
+    #[test]
+    fn wide_lines_are_left_alone() {
+        let mut output = String::new();
+        let mut padded = padded(&mut output, 3);
+        padded.write_str("日本\n").unwrap();
+        padded.write_str("abcd\n").unwrap();
+        padded.finish().unwrap();
+
+        assert_eq!(output, "日本\nabcd\n");
+    }
+}
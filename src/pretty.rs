@@ -0,0 +1,257 @@
+use crate::{indented, Format, Indent, Indentation, DEFAULT_INDENTATION};
+use core::fmt::{self, Write};
+
+/// A value that can pretty print itself as nested, indented output
+///
+/// Implementations write their own line through the `IndentWriter` and hand their children to
+/// `IndentWriter::nest`, which takes care of indenting them one level deeper. This keeps the
+/// depth bookkeeping out of the implementations themselves.
+///
+/// ```rust
+/// use core::fmt::{self, Write};
+/// use indenter::{IndentWriter, Pretty, PrettyPrint};
+///
+/// enum Expr {
+///     Num(i64),
+///     Add(Box<Expr>, Box<Expr>),
+/// }
+///
+/// impl PrettyPrint for Expr {
+///     fn pretty(&self, w: &mut IndentWriter<'_>) -> fmt::Result {
+///         match self {
+///             Expr::Num(n) => writeln!(w, "{}", n),
+///             Expr::Add(lhs, rhs) => {
+///                 writeln!(w, "Add")?;
+///                 w.nest(&**lhs)?;
+///                 w.nest(&**rhs)
+///             }
+///         }
+///     }
+/// }
+///
+/// let expr = Expr::Add(Box::new(Expr::Num(1)), Box::new(Expr::Num(2)));
+///
+/// assert_eq!(Pretty::new(&expr).with_str("  ").to_string(), "Add\n  1\n  2\n");
+/// ```
+pub trait PrettyPrint {
+    /// Write this value to `w`
+    fn pretty(&self, w: &mut IndentWriter<'_>) -> fmt::Result;
+}
+
+impl<T: PrettyPrint + ?Sized> PrettyPrint for &T {
+    fn pretty(&self, w: &mut IndentWriter<'_>) -> fmt::Result {
+        (**self).pretty(w)
+    }
+}
+
+/// A writer that indents its output according to the current nesting depth
+///
+/// Every line written is prefixed with the indentation unit repeated once per level of depth.
+/// Empty lines are left empty, like with `Indented`.
+#[allow(missing_debug_implementations)]
+pub struct IndentWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    unit: &'a str,
+    depth: Indent,
+    /// The depth below which the indentation is written by `inner`, for writers of `with_indent`
+    base: Indent,
+    /// The indentation written before the levels above `base`
+    extra: &'a str,
+    needs_indent: bool,
+}
+
+impl<'a> IndentWriter<'a> {
+    /// Wrap `inner`, indenting each level of depth with `unit`
+    pub fn new(inner: &'a mut dyn fmt::Write, unit: &'a str) -> Self {
+        Self::at_depth(inner, unit, Indent::ZERO)
+    }
+
+    /// Wrap `inner`, starting out `depth` levels deep
+    pub(crate) fn at_depth(inner: &'a mut dyn fmt::Write, unit: &'a str, depth: Indent) -> Self {
+        Self {
+            inner,
+            unit,
+            depth,
+            base: Indent::ZERO,
+            extra: "",
+            needs_indent: true,
+        }
+    }
+
+    /// The current nesting depth
+    pub fn depth(&self) -> Indent {
+        self.depth
+    }
+
+    /// Pretty print `value` one level deeper than the current depth
+    pub fn nest(&mut self, value: &dyn PrettyPrint) -> fmt::Result {
+        self.nested(|w| value.pretty(w))
+    }
+
+    /// Run `f` one level deeper than the current depth
+    ///
+    /// The depth is restored afterwards, even when `f` returns an error.
+    pub fn nested<F>(&mut self, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Self) -> fmt::Result,
+    {
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Run `f` with every line indented by `indentation` after the current indentation
+    ///
+    /// This is for one-off indentation that isn't a level of depth, such as lining up the lines
+    /// of a value under a field name. `f` is given a writer that writes through this one, so
+    /// nothing has to be restored afterwards, even when `f` returns early with an error. Nesting
+    /// within `f` adds levels after `indentation`.
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use indenter::IndentWriter;
+    ///
+    /// let mut output = String::new();
+    /// let mut w = IndentWriter::new(&mut output, "  ");
+    /// w.nested(|w| {
+    ///     write!(w, "args: ")?;
+    ///     w.with_indent("      ", |w| {
+    ///         writeln!(w, "--verbose")?;
+    ///         w.nested(|w| writeln!(w, "--level 2"))
+    ///     })
+    /// })?;
+    ///
+    /// assert_eq!(output, "  args: --verbose\n          --level 2\n");
+    /// # Ok::<(), core::fmt::Error>(())
+    /// ```
+    pub fn with_indent<'s, F>(&'s mut self, indentation: &'s str, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut IndentWriter<'s>) -> fmt::Result,
+    {
+        let (unit, depth, needs_indent) = (self.unit, self.depth, self.needs_indent);
+        let mut scoped = IndentWriter {
+            inner: self,
+            unit,
+            depth,
+            base: depth,
+            extra: indentation,
+            needs_indent,
+        };
+
+        f(&mut scoped)
+    }
+}
+
+impl fmt::Write for IndentWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let extra = self.extra;
+        let indentation = Indentation::new(self.depth - self.base.level(), self.unit);
+        let mut inserter = |_, f: &mut dyn Write| write!(f, "{}{}", extra, indentation);
+        let mut indented = indented(self.inner).with_format(Format::Custom {
+            inserter: &mut inserter,
+        });
+
+        indented.needs_indent = self.needs_indent;
+        let result = indented.write_str(s);
+        self.needs_indent = indented.needs_indent;
+        result
+    }
+}
+
+/// Helper struct for displaying a `PrettyPrint` value
+///
+/// The value is written at depth zero, nesting with `DEFAULT_INDENTATION` per level unless
+/// configured otherwise.
+#[derive(Debug, Clone, Copy)]
+pub struct Pretty<'a, T: ?Sized> {
+    value: &'a T,
+    unit: &'a str,
+}
+
+impl<'a, T: PrettyPrint + ?Sized> Pretty<'a, T> {
+    /// Display `value` with the default unit, `DEFAULT_INDENTATION`
+    pub fn new(value: &'a T) -> Self {
+        Self {
+            value,
+            unit: DEFAULT_INDENTATION,
+        }
+    }
+
+    /// Sets the unit repeated once per level of depth
+    pub fn with_str(mut self, unit: &'a str) -> Self {
+        self.unit = unit;
+        self
+    }
+}
+
+impl<T: PrettyPrint + ?Sized> fmt::Display for Pretty<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.pretty(&mut IndentWriter::new(f, self.unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::{String, ToString};
+
+    struct Block(&'static [Stmt]);
+
+    enum Stmt {
+        Line(&'static str),
+        Block(Block),
+    }
+
+    impl PrettyPrint for Block {
+        fn pretty(&self, w: &mut IndentWriter<'_>) -> fmt::Result {
+            writeln!(w, "{{")?;
+            for stmt in self.0 {
+                w.nest(stmt)?;
+            }
+            writeln!(w, "}}")
+        }
+    }
+
+    impl PrettyPrint for Stmt {
+        fn pretty(&self, w: &mut IndentWriter<'_>) -> fmt::Result {
+            match self {
+                Stmt::Line(line) => writeln!(w, "{}", line),
+                Stmt::Block(block) => block.pretty(w),
+            }
+        }
+    }
+
+    #[test]
+    fn nested_blocks() {
+        let block = Block(&[Stmt::Line("a\nb"), Stmt::Block(Block(&[Stmt::Line("c")]))]);
+        let expected = "{\n\ta\n\tb\n\t{\n\t\tc\n\t}\n}\n";
+
+        assert_eq!(Pretty::new(&block).with_str("\t").to_string(), expected);
+    }
+
+    #[test]
+    fn depth_restored_on_error() {
+        let mut output = String::new();
+        let mut w = IndentWriter::new(&mut output, "  ");
+
+        assert!(w.nested(|_| Err(fmt::Error)).is_err());
+        assert_eq!(w.depth(), Indent::ZERO);
+    }
+
+    #[test]
+    fn with_indent_stacks() {
+        let mut output = String::new();
+        let mut w = IndentWriter::new(&mut output, "  ");
+        w.with_indent("| ", |w| {
+            writeln!(w, "a")?;
+            w.with_indent("> ", |w| writeln!(w, "b\n\nc"))
+        })
+        .unwrap();
+        writeln!(w, "d").unwrap();
+
+        assert_eq!(output, "| a\n| > b\n\n| > c\nd\n");
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{Indent, Indentation, DEFAULT_INDENTATION};
+use core::fmt::{self, Write};
+
+/// Helper struct for previewing generated code with brace-driven indentation
+///
+/// # Explanation
+///
+/// Code generated by procedural macros is usually rendered as a single line of tokens, which is
+/// unreadable once it grows past a few items. This type re-flows such code: a line break follows
+/// every `{` and `}`, as well as every `;` and `,` not inside parentheses or brackets, and each
+/// line is indented by how many braces it is nested in. Whitespace is collapsed and the contents
+/// of string and character literals are left untouched. It is not a formatter, so braces in
+/// comments are taken at face value, but it makes debug output of generated code readable without
+/// pulling one in.
+///
+/// ```rust
+/// use indenter::code_preview;
+///
+/// let code = "impl Foo { fn foo (& self) { if x { 1 } else { 2 } ; } }";
+///
+/// assert_eq!(
+///     code_preview(code).with_str("  ").to_string(),
+///     "impl Foo {\n  fn foo (& self) {\n    if x {\n      1\n    } else {\n      2\n    };\n  }\n}\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CodePreview<'a> {
+    code: &'a str,
+    unit: &'static str,
+}
+
+/// Create a `CodePreview` of `code` indented with `DEFAULT_INDENTATION` per brace
+pub fn code_preview(code: &str) -> CodePreview<'_> {
+    CodePreview {
+        code,
+        unit: DEFAULT_INDENTATION,
+    }
+}
+
+/// Render `tokens` as a `code_preview`
+///
+/// Requires the `std` and `proc-macro2` features.
+#[cfg(all(feature = "std", feature = "proc-macro2"))]
+pub fn token_preview(tokens: &proc_macro2::TokenStream) -> String {
+    code_preview(&tokens.to_string()).to_string()
+}
+
+impl CodePreview<'_> {
+    /// Sets the string repeated once per level of nesting
+    pub fn with_str(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+}
+
+impl fmt::Display for CodePreview<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = Reflow {
+            f,
+            unit: self.unit,
+            depth: Indent::ZERO,
+            line_start: true,
+            space: false,
+        };
+        let mut delimiters = Delimiters::default();
+        // Inside a string literal, whether the previous character escapes the next one
+        let mut string: Option<bool> = None;
+        // The end of the character literal being written
+        let mut literal_end = 0;
+        let mut closed = false;
+
+        for (i, c) in self.code.char_indices() {
+            if i < literal_end {
+                out.push(c)?;
+                continue;
+            }
+
+            if let Some(escaped) = string {
+                out.push(c)?;
+                string = match c {
+                    '\\' if !escaped => Some(true),
+                    '"' if !escaped => None,
+                    _ => Some(false),
+                };
+                continue;
+            }
+
+            if c.is_whitespace() {
+                out.space = true;
+                continue;
+            }
+
+            // Keep punctuation and `else` that follow a closing brace on the same line
+            if closed {
+                closed = false;
+                match c {
+                    ';' | ',' | ')' | ']' | '.' | '?' => out.space = false,
+                    _ if self.code[i..].starts_with("else") => {}
+                    _ => out.newline()?,
+                }
+            }
+
+            match c {
+                '"' => {
+                    out.push(c)?;
+                    string = Some(false);
+                }
+                '\'' => {
+                    out.push(c)?;
+                    literal_end = i + char_literal_len(&self.code[i..]).unwrap_or(0);
+                }
+                '{' => {
+                    out.push(c)?;
+                    out.newline()?;
+                    out.depth += 1;
+                    delimiters.push(true);
+                }
+                '}' => {
+                    delimiters.pop();
+                    out.depth -= 1;
+                    out.newline()?;
+                    out.push(c)?;
+                    closed = true;
+                }
+                '(' | '[' => {
+                    out.push(c)?;
+                    delimiters.push(false);
+                }
+                ')' | ']' => {
+                    delimiters.pop();
+                    out.push(c)?;
+                }
+                ';' => {
+                    out.space = false;
+                    out.push(c)?;
+                    if delimiters.statement_level() {
+                        out.newline()?;
+                    }
+                }
+                ',' => {
+                    out.space = false;
+                    out.push(c)?;
+                    if delimiters.statement_level() {
+                        out.newline()?;
+                    }
+                }
+                _ => out.push(c)?,
+            }
+        }
+
+        out.newline()
+    }
+}
+
+/// Returns the length of the character literal `code` starts with, or `None` for a lifetime
+fn char_literal_len(code: &str) -> Option<usize> {
+    let mut chars = code[1..].chars();
+    let first = chars.next()?;
+
+    if first == '\\' {
+        let escaped = 2 + chars.next()?.len_utf8();
+        return code[escaped..].find('\'').map(|end| escaped + end + 1);
+    }
+
+    match chars.next() {
+        Some('\'') => Some(first.len_utf8() + 2),
+        _ => None,
+    }
+}
+
+/// Writes re-flowed code, indenting each line when its first character is written
+struct Reflow<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    unit: &'static str,
+    depth: Indent,
+    line_start: bool,
+    space: bool,
+}
+
+impl Reflow<'_, '_> {
+    fn push(&mut self, c: char) -> fmt::Result {
+        if self.line_start {
+            write!(self.f, "{}", Indentation::new(self.depth, self.unit))?;
+            self.line_start = false;
+        } else if self.space {
+            self.f.write_char(' ')?;
+        }
+
+        self.space = false;
+        self.f.write_char(c)
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        self.space = false;
+
+        if self.line_start {
+            return Ok(());
+        }
+
+        self.line_start = true;
+        self.f.write_char('\n')
+    }
+}
+
+/// A fixed size stack recording whether each open delimiter is a brace
+///
+/// Nesting beyond 128 delimiters is counted but not recorded, those levels are treated as braces.
+#[derive(Default)]
+struct Delimiters {
+    braces: u128,
+    len: u32,
+}
+
+impl Delimiters {
+    fn push(&mut self, brace: bool) {
+        if self.len < u128::BITS {
+            self.braces = self.braces << 1 | brace as u128;
+        }
+        self.len += 1;
+    }
+
+    fn pop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        self.len -= 1;
+        if self.len < u128::BITS {
+            self.braces >>= 1;
+        }
+    }
+
+    /// Whether the innermost open delimiter, if any, is a brace
+    fn statement_level(&self) -> bool {
+        self.len == 0 || self.len > u128::BITS || self.braces & 1 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn struct_fields_and_calls() {
+        let code = r#"struct A { a : u8 , b : [u8 ; 2] , } fn f () { g (1 , "{ ; }") ; }"#;
+        let expected =
+            "struct A {\n    a : u8,\n    b : [u8; 2],\n}\nfn f () {\n    g (1, \"{ ; }\");\n}\n";
+
+        assert_eq!(code_preview(code).with_str("    ").to_string(), expected);
+    }
+
+    #[test]
+    fn escaped_quotes_in_strings() {
+        let code = r#"{ "a \" { b" }"#;
+
+        assert_eq!(
+            code_preview(code).with_str("    ").to_string(),
+            "{\n    \"a \\\" { b\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn char_literals_and_lifetimes() {
+        let code = r#"fn f<'a>(s: &'a str) { let c = '"'; let d = '\''; let e = '{'; if x { y } }"#;
+        let expected = "fn f<'a>(s: &'a str) {\n  let c = '\"';\n  let d = '\\'';\n  \
+                        let e = '{';\n  if x {\n    y\n  }\n}\n";
+
+        assert_eq!(code_preview(code).with_str("  ").to_string(), expected);
+    }
+
+    #[cfg(all(feature = "std", feature = "proc-macro2"))]
+    #[test]
+    fn token_stream() {
+        let tokens: proc_macro2::TokenStream = "mod m { fn f() -> u8 { 1 } }".parse().unwrap();
+
+        assert_eq!(
+            token_preview(&tokens),
+            alloc::format!(
+                "mod m {{\n{0}fn f () -> u8 {{\n{0}{0}1\n{0}}}\n}}\n",
+                DEFAULT_INDENTATION
+            )
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{indented, Format, DEFAULT_INDENTATION};
+use core::fmt::{self, Write};
+use log::Record;
+
+/// Helper struct for rendering the message of a `log::Record` with indented continuation lines
+///
+/// # Explanation
+///
+/// Custom `Log` implementations usually print a header such as the level and target followed by
+/// the message. Multi-line messages then continue at column zero, which makes them hard to tell
+/// apart from the next record. `IndentedRecord` writes the message through `Indented` so the
+/// first line follows the header and every later line carries the configured prefix.
+///
+/// ```rust
+/// use indenter::IndentedRecord;
+///
+/// let record = log::Record::builder()
+///     .args(format_args!("first\nsecond"))
+///     .level(log::Level::Warn)
+///     .build();
+///
+/// let header = format!("[{}] ", record.level());
+/// let output = format!("{}{}", header, IndentedRecord::new(&record).with_width(header.len()));
+///
+/// assert_eq!(output, "[WARN] first\n       second");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct IndentedRecord<'a, 'r> {
+    record: &'a Record<'r>,
+    prefix: Prefix,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Prefix {
+    Str(&'static str),
+    Width(usize),
+}
+
+impl<'a, 'r> IndentedRecord<'a, 'r> {
+    /// Wrap `record`, indenting continuation lines with `DEFAULT_INDENTATION`
+    pub fn new(record: &'a Record<'r>) -> Self {
+        Self {
+            record,
+            prefix: Prefix::Str(DEFAULT_INDENTATION),
+        }
+    }
+
+    /// Sets the string used to indent continuation lines
+    pub fn with_str(mut self, indentation: &'static str) -> Self {
+        self.prefix = Prefix::Str(indentation);
+        self
+    }
+
+    /// Indent continuation lines with `width` spaces, usually the width of the record's header
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.prefix = Prefix::Width(width);
+        self
+    }
+}
+
+impl fmt::Display for IndentedRecord<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = self.prefix;
+        let mut pad = move |_, f: &mut dyn Write| match prefix {
+            Prefix::Str(indentation) => f.write_str(indentation),
+            Prefix::Width(width) => write!(f, "{:width$}", "", width = width),
+        };
+
+        // The message continues the header's line, only the lines after it are indented
+        let mut indented = indented(f).with_format(Format::Custom { inserter: &mut pad });
+        indented.needs_indent = false;
+
+        indented.write_fmt(*self.record.args())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_line() {
+        let record = Record::builder().args(format_args!("hello")).build();
+
+        assert_eq!(IndentedRecord::new(&record).to_string(), "hello");
+    }
+
+    #[test]
+    fn with_str() {
+        let record = Record::builder().args(format_args!("a\nb\n")).build();
+
+        assert_eq!(
+            IndentedRecord::new(&record).with_str("| ").to_string(),
+            "a\n| b\n"
+        );
+    }
+}
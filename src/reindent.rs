@@ -0,0 +1,144 @@
+use crate::spaces::write_spaces;
+use crate::{Indent, Indentation};
+use core::fmt::{self, Write};
+
+/// A writer that rewrites the leading indentation of every line from one unit to another
+///
+/// # Explanation
+///
+/// Output such as `{:#?}` is indented by four spaces per level. This writer counts the leading
+/// spaces of every line as it streams through, and writes the same depth in another unit, such as
+/// a tab or two spaces, keeping the relative depth of every line. Spaces left over after the last
+/// whole level are kept as they are. The indentation is measured as it arrives, so it may be split
+/// across writes.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::Reindent;
+///
+/// let mut output = String::new();
+/// write!(Reindent::new(&mut output, "\t"), "Point {{\n    x: 1,\n        y: 2,\n}}")?;
+///
+/// assert_eq!(output, "Point {\n\tx: 1,\n\t\ty: 2,\n}");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct Reindent<'a, W: ?Sized> {
+    inner: &'a mut W,
+    unit: &'a str,
+    width: usize,
+    /// The number of leading spaces counted on the current line, or `None` past them
+    leading: Option<usize>,
+}
+
+impl<'a, W: ?Sized> Reindent<'a, W> {
+    /// Construct a writer that rewrites indentation of four spaces per level to `unit`
+    pub fn new(inner: &'a mut W, unit: &'a str) -> Self {
+        Self {
+            inner,
+            unit,
+            width: 4,
+            leading: Some(0),
+        }
+    }
+
+    /// Sets the number of spaces per level in the input
+    pub fn from_width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+}
+
+impl<W: fmt::Write + ?Sized> Reindent<'_, W> {
+    /// Writes the indentation counted so far in the new unit
+    fn write_leading(&mut self, spaces: usize) -> fmt::Result {
+        let levels = Indentation::new(Indent(spaces / self.width), self.unit);
+        write!(self.inner, "{}", levels)?;
+        write_spaces(self.inner, spaces % self.width)
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Reindent<'_, W> {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        while !s.is_empty() {
+            let spaces = match self.leading {
+                Some(spaces) => spaces,
+                None => match s.find('\n') {
+                    Some(end) => {
+                        self.inner.write_str(&s[..=end])?;
+                        self.leading = Some(0);
+                        s = &s[end + 1..];
+                        continue;
+                    }
+                    None => return self.inner.write_str(s),
+                },
+            };
+
+            let rest = s.trim_start_matches(' ');
+            let spaces = spaces + (s.len() - rest.len());
+            s = rest;
+
+            if s.is_empty() {
+                self.leading = Some(spaces);
+            } else {
+                self.write_leading(spaces)?;
+                self.leading = None;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Helper struct for displaying the `{:#?}` output of a value with a different indentation unit
+///
+/// See `reindent_debug` for details.
+#[derive(Debug, Clone, Copy)]
+pub struct ReindentDebug<'a, T: ?Sized> {
+    value: &'a T,
+    unit: &'a str,
+}
+
+/// Create a `ReindentDebug` that displays `value` pretty printed with `unit` per level
+///
+/// ```rust
+/// use indenter::reindent_debug;
+///
+/// let value = Some(vec![1]);
+///
+/// assert_eq!(
+///     reindent_debug(&value, "  ").to_string(),
+///     "Some(\n  [\n    1,\n  ],\n)"
+/// );
+/// ```
+pub fn reindent_debug<'a, T: fmt::Debug + ?Sized>(
+    value: &'a T,
+    unit: &'a str,
+) -> ReindentDebug<'a, T> {
+    ReindentDebug { value, unit }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Display for ReindentDebug<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(Reindent::new(f, self.unit), "{:#?}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn split_indentation_and_leftover_spaces() {
+        let mut output = String::new();
+        let mut reindent = Reindent::new(&mut output, "-").from_width(2);
+        reindent.write_str("a\n ").unwrap();
+        reindent.write_str("    b\n  ").unwrap();
+        reindent.write_str("\nc").unwrap();
+
+        assert_eq!(output, "a\n-- b\n-\nc");
+    }
+}
@@ -0,0 +1,107 @@
+use crate::indented;
+use core::fmt::{self, Write};
+use std::error::Error;
+
+/// Helper struct for displaying an error together with the chain of its sources
+///
+/// # Explanation
+///
+/// The `Display` impl of an error only describes the error itself, and leaves its sources for
+/// the reporter to walk. This writes the error, followed by every source numbered and indented
+/// under a `Caused by:` heading, the way `eyre` reports errors, so a multi-line source message
+/// stays readable. Errors without a source are written on their own. This is behind the `std`
+/// feature, and `#[derive(IndentedReport)]` from the `derive` feature adds a `report` method that
+/// returns one.
+///
+/// ```rust
+/// use std::{error::Error, fmt};
+/// use indenter::ErrorReport;
+///
+/// #[derive(Debug)]
+/// struct LoadError(std::num::ParseIntError);
+///
+/// impl fmt::Display for LoadError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str("failed to load the config")
+///     }
+/// }
+///
+/// impl Error for LoadError {
+///     fn source(&self) -> Option<&(dyn Error + 'static)> {
+///         Some(&self.0)
+///     }
+/// }
+///
+/// let error = LoadError("x".parse::<u8>().unwrap_err());
+///
+/// assert_eq!(
+///     ErrorReport::new(&error).to_string(),
+///     "failed to load the config\n\nCaused by:\n   0: invalid digit found in string"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorReport<'a>(&'a (dyn Error + 'static));
+
+impl<'a> ErrorReport<'a> {
+    /// Construct a report of `error` and its sources
+    pub fn new(error: &'a (dyn Error + 'static)) -> Self {
+        Self(error)
+    }
+}
+
+impl fmt::Display for ErrorReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+
+        let mut source = self.0.source();
+        if source.is_some() {
+            write!(f, "\n\nCaused by:")?;
+        }
+
+        let mut ind = 0;
+        while let Some(error) = source {
+            writeln!(f)?;
+            write!(indented(f).ind(ind), "{}", error)?;
+
+            source = error.source();
+            ind += 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Layer(&'static str, Option<Box<Layer>>);
+
+    impl fmt::Display for Layer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl Error for Layer {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.1.as_deref().map(|layer| layer as _)
+        }
+    }
+
+    #[test]
+    fn multi_line_sources() {
+        let inner = Layer("disk full\nwhile writing", None);
+        let error = Layer(
+            "save failed",
+            Some(Box::new(Layer("flush", Some(Box::new(inner))))),
+        );
+
+        assert_eq!(
+            ErrorReport::new(&error).to_string(),
+            "save failed\n\nCaused by:\n   0: flush\n   1: disk full\n      while writing"
+        );
+        assert_eq!(ErrorReport::new(&Layer("alone", None)).to_string(), "alone");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{indented, Format, Indent, Indentation};
+use core::fmt::{self, Write};
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+std::thread_local! {
+    static DEPTH: Cell<Indent> = const { Cell::new(Indent::ZERO) };
+}
+
+/// Nest output on the current thread one level deeper, until the returned guard is dropped
+///
+/// # Explanation
+///
+/// Logging calls deep inside a request handler or a build step can't easily be handed an
+/// indenter. Instead, the code that enters a logical scope pushes one, and anything logged while
+/// the guard is alive can ask for the depth of the current thread with `current_depth` or the
+/// `indent_current!` macro. Scopes are per thread, so work on other threads is not affected.
+/// This is behind the `std` feature.
+///
+/// ```rust
+/// use indenter::{indent_current, push_scope};
+///
+/// let mut log = Vec::new();
+/// log.push(indent_current!(unit = "    ", "handling request").to_string());
+/// {
+///     let _scope = push_scope();
+///     log.push(indent_current!(unit = "    ", "parsing body\nof 2 bytes").to_string());
+/// }
+/// log.push(indent_current!(unit = "    ", "done").to_string());
+///
+/// assert_eq!(log, ["handling request", "    parsing body\n    of 2 bytes", "done"]);
+/// ```
+pub fn push_scope() -> ScopeGuard {
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+    ScopeGuard {
+        _thread: PhantomData,
+    }
+}
+
+/// Leave the scope entered by the `push_scope` that returned `guard`
+///
+/// This is the same as dropping the guard, for code that reads better with the end of a scope
+/// spelled out.
+pub fn pop_scope(guard: ScopeGuard) {
+    drop(guard);
+}
+
+/// Returns how many scopes the current thread is nested in
+pub fn current_depth() -> Indent {
+    DEPTH.with(Cell::get)
+}
+
+/// A guard that leaves the scope entered by `push_scope` when it is dropped
+///
+/// The guard can't be sent to another thread, since it has to be dropped on the thread whose
+/// depth it changed.
+#[derive(Debug)]
+#[must_use = "the scope is left as soon as the guard is dropped"]
+pub struct ScopeGuard {
+    _thread: PhantomData<*const ()>,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Helper struct for displaying a value indented by the depth of the current thread
+///
+/// This is what `indent_current!` returns with format arguments. The depth is read when it is
+/// constructed.
+#[derive(Debug, Clone, Copy)]
+pub struct Scoped<'a, T> {
+    value: T,
+    indentation: Indentation<'a>,
+}
+
+impl<'a, T: fmt::Display> Scoped<'a, T> {
+    /// Display `value` with every line indented by `unit` once per scope of the current thread
+    pub fn new(value: T, unit: &'a str) -> Self {
+        Self {
+            value,
+            indentation: Indentation::new(current_depth(), unit),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Scoped<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indentation = self.indentation;
+        let mut inserter = |_, f: &mut dyn Write| write!(f, "{}", indentation);
+
+        write!(
+            indented(f).with_format(Format::Custom {
+                inserter: &mut inserter,
+            }),
+            "{}",
+            self.value
+        )
+    }
+}
+
+/// Returns the indentation of the current thread's depth, or formats a message indented by it
+///
+/// Without arguments this evaluates to the `Indentation` of `current_depth` with
+/// `DEFAULT_INDENTATION` as its unit, to put in front of a single line. With format arguments it
+/// evaluates to a `Scoped` that indents every line of the formatted message, for passing on to a
+/// logging macro. Either form takes `unit = "..."` first to use another unit. This is behind the
+/// `std` feature.
+///
+/// ```rust
+/// use indenter::{indent_current, push_scope};
+///
+/// let _request = push_scope();
+/// let _phase = push_scope();
+///
+/// assert_eq!(format!("{}retrying", indent_current!(unit = "  ")), "    retrying");
+/// assert_eq!(indent_current!(unit = "| ", "a\nb").to_string(), "| | a\n| | b");
+/// ```
+#[macro_export]
+macro_rules! indent_current {
+    () => {
+        $crate::indent_current!(unit = $crate::DEFAULT_INDENTATION)
+    };
+    (unit = $unit:expr) => {
+        $crate::Indentation::new($crate::current_depth(), $unit)
+    };
+    (unit = $unit:expr, $($arg:tt)+) => {
+        $crate::Scoped::new(::core::format_args!($($arg)+), $unit)
+    };
+    ($($arg:tt)+) => {
+        $crate::indent_current!(unit = $crate::DEFAULT_INDENTATION, $($arg)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_are_per_thread() {
+        let outer = push_scope();
+        let inner = push_scope();
+        assert_eq!(current_depth(), Indent(2));
+
+        std::thread::spawn(|| assert_eq!(current_depth(), Indent::ZERO))
+            .join()
+            .unwrap();
+
+        pop_scope(inner);
+        assert_eq!(current_depth(), Indent(1));
+        drop(outer);
+        assert_eq!(current_depth(), Indent::ZERO);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{indented, DEFAULT_INDENTATION};
+use core::fmt::{self, Write};
+
+/// Helper struct for rendering a titled, indented block of output
+///
+/// # Explanation
+///
+/// Report handlers tend to print the same shape over and over: a header such as `Location:` or
+/// `Caused by:` on its own line, followed by the body of the section indented underneath it.
+/// `Section` renders that shape through `Indented` so every handler lays sections out the same
+/// way.
+///
+/// ```rust
+/// use indenter::Section;
+///
+/// let section = Section::new("Location:", "src/main.rs:3:5").with_str("    ");
+///
+/// assert_eq!(section.to_string(), "Location:\n    src/main.rs:3:5");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Section<T, B> {
+    title: T,
+    body: B,
+    indentation: &'static str,
+    separated: bool,
+}
+
+impl<T, B> Section<T, B> {
+    /// Construct a section with the default indentation, `DEFAULT_INDENTATION`
+    pub fn new(title: T, body: B) -> Self {
+        Self {
+            title,
+            body,
+            indentation: DEFAULT_INDENTATION,
+            separated: false,
+        }
+    }
+
+    /// Sets the string used to indent the body of the section
+    pub fn with_str(mut self, indentation: &'static str) -> Self {
+        self.indentation = indentation;
+        self
+    }
+
+    /// Separate the section from any preceding output with a blank line
+    ///
+    /// The section then starts by ending the current line and writing an empty one, which is what
+    /// handlers want when appending sections after the error message itself.
+    pub fn separated(mut self) -> Self {
+        self.separated = true;
+        self
+    }
+}
+
+impl<T, B> fmt::Display for Section<T, B>
+where
+    T: fmt::Display,
+    B: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.separated {
+            f.write_str("\n\n")?;
+        }
+
+        write!(f, "{}", self.title)?;
+        f.write_char('\n')?;
+        write!(indented(f).with_str(self.indentation), "{}", self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn multi_line_body() {
+        let section = Section::new("Caused by:", "first\nsecond");
+
+        assert_eq!(
+            section.to_string(),
+            alloc::format!("Caused by:\n{0}first\n{0}second", DEFAULT_INDENTATION)
+        );
+    }
+
+    #[test]
+    fn separated_with_str() {
+        let section = Section::new("Note:", "body").with_str("  ").separated();
+
+        assert_eq!(section.to_string(), "\n\nNote:\n  body");
+    }
+}
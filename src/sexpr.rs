@@ -0,0 +1,210 @@
+use crate::{Indent, Indentation};
+use core::fmt::{self, Write};
+
+/// Where the arguments of a form continue after a line break
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SexprAlign {
+    /// Line up with the first argument, just after the operator
+    Operator,
+    /// Indent by a fixed number of columns past the opening parenthesis
+    Fixed(usize),
+}
+
+/// A writer for nested parenthesized forms, such as IR or AST dumps
+///
+/// # Explanation
+///
+/// Forms are written as closure scopes with `list`, atoms with `atom`. A form stays on one line
+/// until it gets a nested form as anything but its first argument, from then on each argument is
+/// written on its own line, aligned according to `SexprAlign`. The writer tracks the column it is
+/// at to do so, and keeps the rest of its state on the stack, so it does not allocate.
+///
+/// ```rust
+/// use indenter::{SexprAlign, SexprWriter};
+///
+/// let mut output = String::new();
+/// let mut w = SexprWriter::new(&mut output);
+/// w.list("define", |w| {
+///     w.list("f", |w| w.atom("x"))?;
+///     w.list("+", |w| {
+///         w.atom("x")?;
+///         w.atom(1)
+///     })
+/// })?;
+///
+/// assert_eq!(output, "(define (f x)\n        (+ x 1))");
+///
+/// let mut output = String::new();
+/// let mut w = SexprWriter::new(&mut output).with_align(SexprAlign::Fixed(2));
+/// w.list("define", |w| {
+///     w.list("f", |w| w.atom("x"))?;
+///     w.list("+", |w| {
+///         w.atom("x")?;
+///         w.atom(1)
+///     })
+/// })?;
+///
+/// assert_eq!(output, "(define (f x)\n  (+ x 1))");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SexprWriter<W> {
+    inner: Column<W>,
+    align: SexprAlign,
+    form: Form,
+}
+
+/// Layout state of the innermost open form
+#[derive(Debug, Clone, Copy)]
+struct Form {
+    /// The column that arguments continue at after a line break
+    indent: usize,
+    /// Whether no argument has been written yet
+    first: bool,
+    /// Whether arguments have started going on their own lines
+    broken: bool,
+    /// Whether this is the top level rather than a form
+    top: bool,
+}
+
+impl<W: fmt::Write> SexprWriter<W> {
+    /// Construct a writer that aligns arguments with the first argument
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: Column { inner, column: 0 },
+            align: SexprAlign::Operator,
+            form: Form {
+                indent: 0,
+                first: true,
+                broken: true,
+                top: true,
+            },
+        }
+    }
+
+    /// Sets where arguments continue after a line break
+    pub fn with_align(mut self, align: SexprAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Write a form with operator `op`, whose arguments are written by `f`
+    pub fn list<F>(&mut self, op: &str, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Self) -> fmt::Result,
+    {
+        self.begin(true)?;
+
+        let start = self.inner.column;
+        write!(self.inner, "({}", op)?;
+
+        let indent = match self.align {
+            SexprAlign::Operator => self.inner.column + 1,
+            SexprAlign::Fixed(columns) => start + columns,
+        };
+        let parent = core::mem::replace(
+            &mut self.form,
+            Form {
+                indent,
+                first: true,
+                broken: false,
+                top: false,
+            },
+        );
+        let result = f(self);
+        self.form = parent;
+        result?;
+
+        self.inner.write_char(')')
+    }
+
+    /// Write an atom, such as a symbol or a literal
+    pub fn atom(&mut self, atom: impl fmt::Display) -> fmt::Result {
+        self.begin(false)?;
+        write!(self.inner, "{}", atom)
+    }
+
+    /// Consumes this writer, returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner.inner
+    }
+
+    /// Writes the space or line break in front of an argument
+    fn begin(&mut self, list: bool) -> fmt::Result {
+        let form = &mut self.form;
+
+        if form.first {
+            form.first = false;
+            return if form.top {
+                Ok(())
+            } else {
+                self.inner.write_char(' ')
+            };
+        }
+
+        if !(list || form.broken) {
+            return self.inner.write_char(' ');
+        }
+
+        form.broken = true;
+        let indent = Indentation::new(Indent(form.indent), " ");
+        write!(self.inner, "\n{}", indent)
+    }
+}
+
+/// Tracks the column that the next character is written at
+#[derive(Debug)]
+struct Column<W> {
+    inner: W,
+    column: usize,
+}
+
+impl<W: fmt::Write> fmt::Write for Column<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.column = match s.rfind('\n') {
+            Some(pos) => s[pos + 1..].chars().count(),
+            None => self.column + s.chars().count(),
+        };
+
+        self.inner.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn atoms_stay_inline_until_broken() {
+        let mut output = String::new();
+        let mut w = SexprWriter::new(&mut output);
+        w.list("if", |w| {
+            w.list("<", |w| {
+                w.atom("a")?;
+                w.atom("b")
+            })?;
+            w.list("f", |w| w.atom("a"))?;
+            w.atom("b")
+        })
+        .unwrap();
+
+        assert_eq!(output, "(if (< a b)\n    (f a)\n    b)");
+    }
+
+    #[test]
+    fn top_level_forms_on_own_lines() {
+        let mut output = String::new();
+        let mut w = SexprWriter::new(&mut output).with_align(SexprAlign::Fixed(1));
+        w.list("a", |_| Ok(())).unwrap();
+        w.list("b", |w| {
+            w.atom(1)?;
+            w.list("c", |_| Ok(()))
+        })
+        .unwrap();
+
+        assert_eq!(output, "(a)\n(b 1\n (c))");
+    }
+}
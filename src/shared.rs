@@ -0,0 +1,109 @@
+use core::cell::RefCell;
+use core::fmt;
+
+/// A writer for output that lives behind a `RefCell`, such as an `Rc<RefCell<String>>`
+///
+/// # Explanation
+///
+/// `fmt::Write` needs mutable access to the writer, which a buffer shared through a `RefCell` only
+/// hands out one borrow at a time. This adapter borrows the cell for each call to `write_str`
+/// instead of for its whole lifetime, so the buffer can be read in between writes. A write while
+/// the cell is already borrowed fails with `fmt::Error` rather than panicking.
+///
+/// ```rust
+/// use core::cell::RefCell;
+/// use core::fmt::Write;
+/// use indenter::{indented, RefCellWriter};
+/// use std::rc::Rc;
+///
+/// let log = Rc::new(RefCell::new(String::new()));
+/// let mut writer = RefCellWriter::new(&log);
+/// writeln!(indented(&mut writer).with_str("    "), "started")?;
+/// assert_eq!(*log.borrow(), "    started\n");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RefCellWriter<'a, W> {
+    cell: &'a RefCell<W>,
+}
+
+impl<'a, W> RefCellWriter<'a, W> {
+    /// Construct a writer that writes to the contents of `cell`
+    pub fn new(cell: &'a RefCell<W>) -> Self {
+        Self { cell }
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for RefCellWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut inner = self.cell.try_borrow_mut().map_err(|_| fmt::Error)?;
+        inner.write_str(s)
+    }
+}
+
+/// A writer for output that lives behind a `Mutex`, such as an `Arc<Mutex<String>>`
+///
+/// The mutex is locked for each call to `write_str`, so other threads can interleave their own
+/// writes between them. Wrap the output in an `Indented` that is written to in one go to keep it
+/// together. Writing to a poisoned mutex fails with `fmt::Error`.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, MutexWriter};
+/// use std::sync::{Arc, Mutex};
+///
+/// let log = Arc::new(Mutex::new(String::new()));
+/// let worker = {
+///     let log = Arc::clone(&log);
+///     std::thread::spawn(move || {
+///         write!(indented(&mut MutexWriter::new(&log)).with_str("    "), "done\n")
+///     })
+/// };
+/// worker.join().unwrap()?;
+///
+/// assert_eq!(*log.lock().unwrap(), "    done\n");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct MutexWriter<'a, W> {
+    mutex: &'a std::sync::Mutex<W>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, W> MutexWriter<'a, W> {
+    /// Construct a writer that writes to the contents of `mutex`
+    pub fn new(mutex: &'a std::sync::Mutex<W>) -> Self {
+        Self { mutex }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: fmt::Write> fmt::Write for MutexWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut inner = self.mutex.lock().map_err(|_| fmt::Error)?;
+        inner.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn borrowed_cell_is_an_error() {
+        let cell = RefCell::new(String::new());
+        let mut writer = RefCellWriter::new(&cell);
+
+        let guard = cell.borrow();
+        assert!(writer.write_str("a").is_err());
+        drop(guard);
+
+        writer.write_str("b").unwrap();
+        assert_eq!(*cell.borrow(), "b");
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{digits, Caret, Indent, Indentation};
+use core::fmt;
+use core::ops::{Range, RangeInclusive};
+
+/// What `write_snippet` highlights in a snippet
+#[derive(Debug, Clone, Default)]
+pub struct SnippetOptions<'a> {
+    span: Option<Range<usize>>,
+    label: Option<&'a str>,
+    marker: Option<char>,
+}
+
+impl<'a> SnippetOptions<'a> {
+    /// Construct options that highlight nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Underline `span`, a byte range of the whole source, on every line of the snippet it covers
+    pub fn highlight(mut self, span: Range<usize>) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Sets a label written after the underline on the last line of the span
+    pub fn with_label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Sets the character the span is underlined with, `^` by default
+    pub fn with_char(mut self, marker: char) -> Self {
+        self.marker = Some(marker);
+        self
+    }
+}
+
+/// Write `lines` of `source`, counted from 1, with a gutter of line numbers
+///
+/// # Explanation
+///
+/// Every diagnostic that shows the code it is about needs the same block: the lines of code, each
+/// after its right-aligned line number and a `|`, with the span the diagnostic points at
+/// underlined below it by a `Caret`. The gutter is as wide as the largest line number shown.
+/// Lines past the end of `source` are left out, lines may end in `\r\n`, and every line written
+/// ends with a newline. Written to an `Indented`, the whole block is nested under its
+/// indentation.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, write_snippet, SnippetOptions};
+///
+/// let source = "fn main() {\n    let x = 1;\n}\n";
+/// let options = SnippetOptions::new().highlight(20..21).with_label("unused");
+///
+/// let mut output = String::new();
+/// writeln!(output, "warning: unused variable")?;
+/// write_snippet(&mut indented(&mut output).with_str("  "), source, 1..=3, &options)?;
+///
+/// assert_eq!(
+///     output.lines().collect::<Vec<_>>(),
+///     [
+///         "warning: unused variable",
+///         "  1 | fn main() {",
+///         "  2 |     let x = 1;",
+///         "    |         ^ unused",
+///         "  3 | }",
+///     ]
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+pub fn write_snippet(
+    w: &mut dyn fmt::Write,
+    source: &str,
+    lines: RangeInclusive<usize>,
+    options: &SnippetOptions<'_>,
+) -> fmt::Result {
+    let last = (*lines.end()).min(source.lines().count());
+    let width = digits(last);
+    let blank = Indentation::new(Indent(width), " ");
+
+    let mut offset = 0;
+    for (number, line) in source.split('\n').enumerate() {
+        let start = offset;
+        offset += line.len() + 1;
+        let number = number + 1;
+        if !lines.contains(&number) || number > last {
+            continue;
+        }
+
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let end = start + line.len();
+        if line.is_empty() {
+            writeln!(w, "{:>width$} |", number, width = width)?;
+        } else {
+            writeln!(w, "{:>width$} | {}", number, line, width = width)?;
+        }
+
+        let span = match &options.span {
+            Some(span) if span.start <= end && (span.end > start || span.is_empty()) => span,
+            _ => continue,
+        };
+        let local = span.start.max(start) - start..span.end.min(end) - start;
+        let mut caret = Caret::new(line, local).with_char(options.marker.unwrap_or('^'));
+        if let (Some(label), true) = (options.label, span.end <= end) {
+            caret = caret.with_label(label);
+        }
+        writeln!(w, "{} | {}", blank, caret)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn spans_across_lines() {
+        let source = "a\n\nb = [\n  1,\n]";
+        let options = SnippetOptions::new()
+            .highlight(7..15)
+            .with_char('~')
+            .with_label("list");
+
+        let mut output = String::new();
+        write_snippet(&mut output, source, 3..=10, &options).unwrap();
+
+        assert_eq!(
+            output,
+            "3 | b = [\n  |     ~\n4 |   1,\n  | ~~~~\n5 | ]\n  | ~ list\n"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use core::fmt;
+
+/// A run of spaces that longer indentation is written from in chunks
+pub(crate) const SPACES: &str = "                                                                ";
+
+/// A run of tabs that longer indentation is written from in chunks
+pub(crate) const TABS: &str = "\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t";
+
+/// Writes `count` spaces using as few calls to `write_str` as possible
+pub(crate) fn write_spaces(f: &mut (impl fmt::Write + ?Sized), count: usize) -> fmt::Result {
+    write_run(f, SPACES, count)
+}
+
+/// Writes `count` copies of the single byte character `pool` is made of, in chunks of up to the
+/// length of `pool`
+pub(crate) fn write_run(
+    f: &mut (impl fmt::Write + ?Sized),
+    pool: &str,
+    mut count: usize,
+) -> fmt::Result {
+    while count > 0 {
+        let chunk = count.min(pool.len());
+        f.write_str(&pool[..chunk])?;
+        count -= chunk;
+    }
+
+    Ok(())
+}
+
+/// An indenter with a fixed indentation of `N` spaces, known at compile time
+///
+/// # Explanation
+///
+/// `Indented` decides how to indent every line by matching on its `Format` and checking the
+/// options it was configured with. For the common case of indenting by a constant number of
+/// spaces this type skips all of that, writing each prefix straight from a static run of spaces.
+/// Blank lines are left empty, like `Indented` does by default.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::indented_spaces;
+///
+/// let mut output = String::new();
+/// write!(indented_spaces::<2, _>(&mut output), "a\n\nb")?;
+///
+/// assert_eq!(output, "  a\n\n  b");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct SpacesIndented<'a, const N: usize, W: ?Sized> {
+    inner: &'a mut W,
+    needs_indent: bool,
+}
+
+/// Helper function for creating an indenter that indents by `N` spaces
+pub fn indented_spaces<const N: usize, W: ?Sized>(inner: &mut W) -> SpacesIndented<'_, N, W> {
+    SpacesIndented {
+        inner,
+        needs_indent: true,
+    }
+}
+
+impl<const N: usize, W> fmt::Write for SpacesIndented<'_, N, W>
+where
+    W: fmt::Write + ?Sized,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (ind, line) in s.split('\n').enumerate() {
+            if ind > 0 {
+                self.inner.write_char('\n')?;
+                self.needs_indent = true;
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if self.needs_indent {
+                write_spaces(self.inner, N)?;
+                self.needs_indent = false;
+            }
+
+            self.inner.write_str(line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use crate::indented;
+    use alloc::string::String;
+    use core::fmt::Write;
+
+    #[test]
+    fn matches_indented() {
+        let input = "a\n\n  b\nc\n";
+        let mut expected = String::new();
+        let mut output = String::new();
+
+        indented(&mut expected)
+            .with_str("    ")
+            .write_str(input)
+            .unwrap();
+        indented_spaces::<4, _>(&mut output)
+            .write_str(input)
+            .unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn wider_than_pool() {
+        let mut output = String::new();
+        indented_spaces::<100, _>(&mut output)
+            .write_str("x")
+            .unwrap();
+
+        assert_eq!(output.len(), 101);
+        assert!(output.starts_with(SPACES));
+    }
+}
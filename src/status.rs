@@ -0,0 +1,112 @@
+use crate::{indented, Format, Indent, Indentation};
+use core::fmt::{self, Write};
+
+/// Helper struct for displaying a message after a right-aligned status verb, the way cargo does
+///
+/// # Explanation
+///
+/// cargo writes a verb such as `Compiling` or `Finished` right-aligned in a column of 12
+/// characters, in bold green, followed by the message. Continuation lines of the message are
+/// padded with blanks as wide as the gutter, so they line up with the text after the verb. A verb
+/// wider than the gutter widens it for that message. The style of the verb is an ANSI escape
+/// sequence, and is left out with `color(false)`, which can be given `ColorChoice::enabled` with
+/// the `std` feature.
+///
+/// ```rust
+/// use indenter::Status;
+///
+/// let status = Status::new("Compiling", "indenter v0.3.3\nwith features: std")
+///     .color(false)
+///     .to_string();
+///
+/// assert_eq!(
+///     status,
+///     "   Compiling indenter v0.3.3\n             with features: std"
+/// );
+/// assert_eq!(
+///     Status::new("Finished", "dev").to_string(),
+///     "\x1b[1;32m    Finished\x1b[0m dev"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Status<'a, T> {
+    verb: &'a str,
+    message: T,
+    width: usize,
+    style: &'a str,
+    color: bool,
+}
+
+impl<'a, T: fmt::Display> Status<'a, T> {
+    /// Construct a status line with `verb` in bold green in a gutter of 12 columns
+    pub fn new(verb: &'a str, message: T) -> Self {
+        Self {
+            verb,
+            message,
+            width: 12,
+            style: "\x1b[1;32m",
+            color: true,
+        }
+    }
+
+    /// Sets the width the verb is right-aligned in
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the ANSI escape sequence the verb is styled with, such as `"\x1b[1;33m"` for warnings
+    pub fn with_style(mut self, style: &'a str) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets whether the verb is styled
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Status<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.verb.chars().count();
+        let width = self.width.max(len);
+        let padding = Indentation::new(Indent(width - len), " ");
+
+        if self.color {
+            write!(f, "{}{}{}\x1b[0m ", self.style, padding, self.verb)?;
+        } else {
+            write!(f, "{}{} ", padding, self.verb)?;
+        }
+
+        let indentation = Indentation::new(Indent(width + 1), " ");
+        let mut inserter = move |_, f: &mut dyn Write| write!(f, "{}", indentation);
+
+        write!(
+            indented(f)
+                .with_format(Format::Custom {
+                    inserter: &mut inserter,
+                })
+                .skip_lines(1),
+            "{}",
+            self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn wide_verbs_widen_the_gutter() {
+        let status = Status::new("Downloading", "a\nb")
+            .with_width(4)
+            .color(false);
+
+        assert_eq!(status.to_string(), "Downloading a\n            b");
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{indented, Format};
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Indent every line of `s` with `n` spaces and return the result as a new `String`
+///
+/// Empty lines are left empty, matching the behavior of `Indented`.
+///
+/// ```rust
+/// use indenter::indent_all_by;
+///
+/// assert_eq!(indent_all_by(2, "verify\n\nthis"), "  verify\n\n  this");
+/// ```
+pub fn indent_all_by(n: usize, s: &str) -> String {
+    let mut inserter = |_, f: &mut dyn Write| write!(f, "{:n$}", "", n = n);
+
+    indent_all(
+        s,
+        Format::Custom {
+            inserter: &mut inserter,
+        },
+    )
+}
+
+/// Indent every line of `s` with `prefix` and return the result as a new `String`
+///
+/// Empty lines are left empty, matching the behavior of `Indented`.
+///
+/// ```rust
+/// use indenter::indent_all_with;
+///
+/// assert_eq!(indent_all_with("> ", "verify\nthis\n"), "> verify\n> this\n");
+/// ```
+pub fn indent_all_with(prefix: &str, s: &str) -> String {
+    let mut inserter = |_, f: &mut dyn Write| f.write_str(prefix);
+
+    indent_all(
+        s,
+        Format::Custom {
+            inserter: &mut inserter,
+        },
+    )
+}
+
+/// Add `prefix` to every line of `s` that is not blank, with the same signature as
+/// `textwrap::indent`
+///
+/// Lines that consist solely of whitespace are left unchanged.
+///
+/// ```rust
+/// use indenter::indent;
+///
+/// assert_eq!(indent("foo\n  \n  bar\n", "# "), "# foo\n  \n#   bar\n");
+/// ```
+pub fn indent(s: &str, prefix: &str) -> String {
+    indent_where(s, prefix, |line| !line.trim().is_empty())
+}
+
+/// Add `prefix` to every line of `s` for which `predicate` returns true, like Python's
+/// `textwrap.indent`
+///
+/// Unlike `indent`, blank lines are passed to the predicate as well, so a predicate that always
+/// returns true prefixes every line.
+///
+/// ```rust
+/// use indenter::indent_where;
+///
+/// assert_eq!(indent_where("a\n\nb", "> ", |_| true), "> a\n> \n> b");
+/// ```
+pub fn indent_where<P>(s: &str, prefix: &str, mut predicate: P) -> String
+where
+    P: FnMut(&str) -> bool,
+{
+    let mut output = String::with_capacity(s.len());
+    let mut inserter = |_, f: &mut dyn Write| f.write_str(prefix);
+    let mut predicate = |_, line: &str| predicate(line);
+
+    // Writing into a `String` never fails
+    let _ = indented(&mut output)
+        .with_format(Format::Custom {
+            inserter: &mut inserter,
+        })
+        .skip_empty(false)
+        .indent_if(&mut predicate)
+        .write_str(s);
+
+    output
+}
+
+fn indent_all(s: &str, mut format: Format<'_>) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    // Writing into a `String` never fails
+    let _ = indented(&mut output)
+        .with_format(format.reborrow())
+        .write_str(s);
+
+    output
+}
+
+/// Indent every line of `s` with `prefix` in place
+///
+/// This produces the same output as `indent_all_with` without going through a second buffer:
+/// the string grows once to its final size and the prefixes are spliced in back to front, so every
+/// byte is moved at most once.
+///
+/// ```rust
+/// use indenter::indent_in_place;
+///
+/// let mut s = String::from("verify\nthis");
+/// indent_in_place(&mut s, "  ");
+///
+/// assert_eq!(s, "  verify\n  this");
+/// ```
+pub fn indent_in_place(s: &mut String, prefix: &str) {
+    let lines = s.split('\n').filter(|line| !line.is_empty()).count();
+    if lines == 0 || prefix.is_empty() {
+        return;
+    }
+
+    let mut bytes = core::mem::take(s).into_bytes();
+    let mut read = bytes.len();
+    let mut write = read + lines * prefix.len();
+    bytes.resize(write, 0);
+
+    loop {
+        let start = bytes[..read]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |newline| newline + 1);
+        let len = read - start;
+
+        bytes.copy_within(start..read, write - len);
+        write -= len;
+
+        if len > 0 {
+            write -= prefix.len();
+            bytes[write..write + prefix.len()].copy_from_slice(prefix.as_bytes());
+        }
+
+        if start == 0 {
+            break;
+        }
+
+        write -= 1;
+        bytes[write] = b'\n';
+        read = start - 1;
+    }
+
+    *s = match String::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(_) => unreachable!("prefixes are only inserted at line boundaries"),
+    };
+}
+
+/// Remove the leading whitespace common to every non-blank line of `s`
+///
+/// Lines that consist solely of whitespace do not take part in finding the common prefix and are
+/// emptied in the output.
+///
+/// ```rust
+/// use indenter::dedent;
+///
+/// assert_eq!(dedent("    if ok {\n        run()\n    }\n"), "if ok {\n    run()\n}\n");
+/// ```
+pub fn dedent(s: &str) -> String {
+    let mut prefix: Option<&str> = None;
+
+    for line in s.lines().filter(|line| !line.trim().is_empty()) {
+        let leading = &line[..line.len() - line.trim_start().len()];
+
+        prefix = Some(match prefix {
+            None => leading,
+            Some(prefix) => common_prefix(prefix, leading),
+        });
+    }
+
+    let prefix = prefix.unwrap_or_default();
+    let mut output = String::with_capacity(s.len());
+
+    for (ind, line) in s.split('\n').enumerate() {
+        if ind > 0 {
+            output.push('\n');
+        }
+
+        if !line.trim().is_empty() {
+            output.push_str(&line[prefix.len()..]);
+        }
+    }
+
+    output
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| a.len().min(b.len()));
+
+    &a[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indent_all_by_trailing_newline() {
+        assert_eq!(indent_all_by(3, "a\nb\n"), "   a\n   b\n");
+    }
+
+    #[test]
+    fn in_place_matches_indent_all_with() {
+        for input in &["", "\n", "a", "a\n", "\na\n\nb", "héllo\nwörld\n\n"] {
+            let mut s = String::from(*input);
+            indent_in_place(&mut s, "» ");
+
+            assert_eq!(s, indent_all_with("» ", input));
+        }
+    }
+
+    #[test]
+    fn indent_where_sees_whole_lines() {
+        let output = indent_where("- a\n  b\n- c\n", "  ", |line| !line.starts_with('-'));
+
+        assert_eq!(output, "- a\n    b\n- c\n");
+        assert_eq!(indent("\n\na", "> "), "\n\n> a");
+    }
+
+    #[test]
+    fn dedent_mixed_whitespace() {
+        let input = "\t  one\n\t    two\n  \n\t  three";
+
+        assert_eq!(dedent(input), "one\n  two\n\nthree");
+    }
+
+    #[test]
+    fn dedent_then_indent_round_trips() {
+        let input = "one\n  two\n";
+
+        assert_eq!(dedent(&indent_all_with("\t", input)), input);
+    }
+}
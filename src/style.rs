@@ -0,0 +1,81 @@
+use crate::{indented, IndentUnit, Indented, DEFAULT_INDENTATION};
+
+/// The line ending a file is written with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// `\n`, the default
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// `\r`
+    Cr,
+}
+
+impl LineEnding {
+    /// Returns the characters of this line ending
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+}
+
+/// The indentation style of a project, as settings that can be loaded and passed around
+///
+/// # Explanation
+///
+/// Generated code and reports should follow the style of wherever they end up. These settings
+/// collect that style in one place, so it can be read once, from an `.editorconfig` with the `std`
+/// feature or from `detect_indent`, and then used for every indenter. The defaults are
+/// `DEFAULT_INDENTATION`, `\n` line endings, a final newline and colors.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{IndentOptions, IndentUnit};
+///
+/// let options = IndentOptions {
+///     unit: IndentUnit::Tabs,
+///     ..IndentOptions::default()
+/// };
+///
+/// let mut output = String::new();
+/// write!(options.indented(&mut output), "a\nb")?;
+///
+/// assert_eq!(output, "\ta\n\tb");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndentOptions {
+    /// The unit of one level of indentation
+    pub unit: IndentUnit,
+    /// The line ending files are written with
+    pub line_ending: LineEnding,
+    /// Whether files end with a newline
+    pub final_newline: bool,
+    /// Whether gutters and other output may be styled with colors
+    pub color: bool,
+}
+
+impl Default for IndentOptions {
+    fn default() -> Self {
+        Self {
+            unit: match DEFAULT_INDENTATION {
+                "\t" => IndentUnit::Tabs,
+                spaces => IndentUnit::Spaces(spaces.len()),
+            },
+            line_ending: LineEnding::Lf,
+            final_newline: true,
+            color: true,
+        }
+    }
+}
+
+impl IndentOptions {
+    /// Construct an indenter that indents by one level of `unit`
+    pub fn indented<'a, D: ?Sized>(&self, inner: &'a mut D) -> Indented<'a, D> {
+        indented(inner).with_str(self.unit.as_str())
+    }
+}
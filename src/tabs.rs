@@ -0,0 +1,162 @@
+use crate::spaces::write_spaces;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A writer that aligns tab separated cells into columns, like Go's `tabwriter`
+///
+/// # Explanation
+///
+/// Output is buffered until `flush`, and every cell ended by a `\t` is then padded so the cells
+/// of a column line up. As with elastic tabstops, a column is only aligned across a block of
+/// consecutive lines that all have a cell in it, so a line with fewer cells, such as a heading,
+/// starts new columns for the lines after it. The aligned lines are written to the inner writer,
+/// which can be an `Indented` to indent the whole table. This is behind the `alloc` feature,
+/// since the output has to be buffered to measure it.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, TabWriter};
+///
+/// let mut output = String::new();
+/// let mut table = TabWriter::new(indented(&mut output).with_str("    "));
+/// writeln!(table, "test\tresult\ttime")?;
+/// writeln!(table, "parse_empty\tok\t0.01s")?;
+/// writeln!(table, "render\tFAILED\t1.2s")?;
+/// table.flush()?;
+///
+/// assert_eq!(
+///     output,
+///     concat!(
+///         "    test         result  time\n",
+///         "    parse_empty  ok      0.01s\n",
+///         "    render       FAILED  1.2s\n",
+///     )
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct TabWriter<W> {
+    inner: W,
+    buffer: String,
+    padding: usize,
+    min_width: usize,
+}
+
+impl<W: fmt::Write> TabWriter<W> {
+    /// Construct a writer that separates columns by two spaces
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buffer: String::new(),
+            padding: 2,
+            min_width: 0,
+        }
+    }
+
+    /// Sets the number of spaces between the widest cell of a column and the next column
+    pub fn with_padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the minimum width of a cell, not counting padding
+    pub fn with_min_width(mut self, min_width: usize) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    /// Align and write everything buffered so far
+    pub fn flush(&mut self) -> fmt::Result {
+        let buffer = core::mem::take(&mut self.buffer);
+        let lines: Vec<Vec<&str>> = buffer
+            .split('\n')
+            .map(|l| l.split('\t').collect())
+            .collect();
+        let widths = self.widths(&lines);
+
+        for (ind, (cells, widths)) in lines.iter().zip(&widths).enumerate() {
+            if ind > 0 {
+                self.inner.write_char('\n')?;
+            }
+
+            for (cell, width) in cells.iter().zip(widths) {
+                self.inner.write_str(cell)?;
+                write_spaces(&mut self.inner, width - cell.chars().count())?;
+            }
+
+            if let Some(last) = cells.last() {
+                self.inner.write_str(last)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flush the buffered output and return the inner writer
+    pub fn into_inner(mut self) -> Result<W, fmt::Error> {
+        self.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Returns the padded width of every cell ended by a tab, line by line
+    fn widths(&self, lines: &[Vec<&str>]) -> Vec<Vec<usize>> {
+        let mut widths: Vec<Vec<usize>> = lines
+            .iter()
+            .map(|cells| Vec::with_capacity(cells.len() - 1))
+            .collect();
+
+        let columns = lines.iter().map(|cells| cells.len() - 1).max().unwrap_or(0);
+        for column in 0..columns {
+            let mut start = 0;
+            while start < lines.len() {
+                if lines[start].len() - 1 <= column {
+                    start += 1;
+                    continue;
+                }
+
+                let end = lines[start..]
+                    .iter()
+                    .position(|cells| cells.len() - 1 <= column)
+                    .map_or(lines.len(), |len| start + len);
+                let width = lines[start..end]
+                    .iter()
+                    .map(|cells| cells[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(self.min_width);
+
+                for line in &mut widths[start..end] {
+                    line.push(width + self.padding);
+                }
+                start = end;
+            }
+        }
+
+        widths
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for TabWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.buffer.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn columns_end_at_shorter_lines() {
+        let mut table = TabWriter::new(String::new()).with_padding(1);
+        table
+            .write_str("a\tbb\tc\nccc\td\n# heading\nx\ty")
+            .unwrap();
+        let output = table.into_inner().unwrap();
+
+        assert_eq!(output, "a   bb c\nccc d\n# heading\nx y");
+    }
+}
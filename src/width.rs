@@ -0,0 +1,100 @@
+/// Returns the number of terminal columns `text` takes up on a single line
+///
+/// # Explanation
+///
+/// Byte and `char` counts don't match what a terminal shows: East Asian wide characters and most
+/// emoji take two columns, combining marks and control characters take none, and ANSI escape
+/// sequences, such as the colors of a styled gutter, aren't shown at all. This is an
+/// approximation of the widths terminals use that doesn't need the Unicode tables, which is
+/// enough to line up borders and columns around indented output. A tab is counted as one column.
+///
+/// ```rust
+/// use indenter::display_width;
+///
+/// assert_eq!(display_width("abc"), 3);
+/// assert_eq!(display_width("日本"), 4);
+/// assert_eq!(display_width("\x1b[1;32mok\x1b[0m"), 2);
+/// assert_eq!(display_width("e\u{301}"), 1);
+/// ```
+pub fn display_width(text: &str) -> usize {
+    let mut column = Column::default();
+    column.advance(text);
+    column.width
+}
+
+/// A running count of the columns written on a line, that can be fed text in pieces
+///
+/// Escape sequences split across pieces are still skipped, since the state of the sequence is
+/// kept between calls.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Column {
+    pub(crate) width: usize,
+    escape: Escape,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Escape {
+    #[default]
+    None,
+    /// After an `ESC`
+    Start,
+    /// Within the parameters of a control sequence, after `ESC [`
+    Csi,
+}
+
+impl Column {
+    /// Count the columns of `text`, which contains no newlines
+    pub(crate) fn advance(&mut self, text: &str) {
+        for c in text.chars() {
+            self.escape = match (self.escape, c) {
+                (Escape::None, '\x1b') => Escape::Start,
+                (Escape::None, c) => {
+                    self.width += char_width(c);
+                    Escape::None
+                }
+                (Escape::Start, '[') => Escape::Csi,
+                (Escape::Start, _) => Escape::None,
+                (Escape::Csi, '\x40'..='\x7e') => Escape::None,
+                (Escape::Csi, _) => Escape::Csi,
+            };
+        }
+    }
+}
+
+/// Returns the number of columns `c` takes up
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x00..=0x08 | 0x0a..=0x1f | 0x7f..=0x9f => 0,
+        0x0300..=0x036f | 0x200b..=0x200f | 0x20d0..=0x20ff | 0xfe00..=0xfe0f => 0,
+        0x1100..=0x115f
+        | 0x2e80..=0x303e
+        | 0x3041..=0x33ff
+        | 0x3400..=0x4dbf
+        | 0x4e00..=0x9fff
+        | 0xa000..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x1f300..=0x1f64f
+        | 0x1f900..=0x1f9ff
+        | 0x20000..=0x3fffd => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_split_across_pieces() {
+        let mut column = Column::default();
+        for piece in ["a\x1b", "[3", "1mb", "\x1b[0m😀"] {
+            column.advance(piece);
+        }
+
+        assert_eq!(column.width, 4);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::spaces::write_spaces;
+use core::fmt::{self, Write};
+
+/// Helper struct for word wrapping text to a maximum width
+///
+/// # Explanation
+///
+/// Every line of the text is broken greedily between words so that no line is wider than `width`
+/// characters, unless a single word is wider than that on its own. Runs of whitespace between
+/// words are collapsed into single spaces, while line breaks already in the text are kept. The
+/// output contains no indentation of its own, so it can be written through an `Indented` to
+/// indent the wrapped lines, in which case `width` should leave room for the indentation.
+///
+/// ```rust
+/// use core::fmt::Write;
+/// use indenter::{indented, wrap};
+///
+/// let mut output = String::new();
+/// let text = "the quick brown fox jumps over the lazy dog";
+/// write!(indented(&mut output).with_str("> "), "{}", wrap(text, 16))?;
+///
+/// assert_eq!(output, "> the quick brown\n> fox jumps over\n> the lazy dog");
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Wrapped<'a> {
+    text: &'a str,
+    width: usize,
+    column: usize,
+    reflow: bool,
+    markers: &'a [&'a str],
+}
+
+/// The list markers `Wrapped::hang_list_markers` recognizes: bullets and numbers such as `12.`
+///
+/// In a marker pattern, `#` stands for a run of one or more digits.
+pub const LIST_MARKERS: &[&str] = &["- ", "* ", "+ ", "#. ", "#) "];
+
+/// Returns the length in bytes of the list marker `line` starts with, if it matches one of
+/// `markers`
+pub(crate) fn marker_len(line: &str, markers: &[&str]) -> Option<usize> {
+    markers.iter().find_map(|pattern| {
+        let mut len = 0;
+        for c in pattern.chars() {
+            let rest = &line[len..];
+            if c == '#' {
+                let digits =
+                    rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                if digits == 0 {
+                    return None;
+                }
+                len += digits;
+            } else if rest.starts_with(c) {
+                len += c.len_utf8();
+            } else {
+                return None;
+            }
+        }
+
+        Some(len)
+    })
+}
+
+/// Create a `Wrapped` for `text` with lines of at most `width` characters
+pub fn wrap(text: &str, width: usize) -> Wrapped<'_> {
+    Wrapped {
+        text,
+        width,
+        column: 0,
+        reflow: false,
+        markers: &[],
+    }
+}
+
+/// Create a `Wrapped` for `text` that reflows its paragraphs to lines of at most `width`
+/// characters
+///
+/// See `Wrapped::reflow` for details.
+pub fn reflow(text: &str, width: usize) -> Wrapped<'_> {
+    wrap(text, width).reflow()
+}
+
+impl<'a> Wrapped<'a> {
+    /// Sets the column the first line starts at, when output was already written on that line
+    pub fn start_column(mut self, column: usize) -> Self {
+        self.column = column;
+        self
+    }
+
+    /// Join lines that were already wrapped back into paragraphs before wrapping them again
+    ///
+    /// Paragraphs are separated by blank lines, which are collapsed into a single blank line in
+    /// the output. This is meant for prose such as doc comments or commit messages, which arrive
+    /// wrapped to some other width.
+    ///
+    /// ```rust
+    /// use indenter::reflow;
+    ///
+    /// let text = "a short\nparagraph\n\n\n\nand\nanother one";
+    ///
+    /// assert_eq!(
+    ///     reflow(text, 12).to_string(),
+    ///     "a short\nparagraph\n\nand another\none"
+    /// );
+    /// ```
+    pub fn reflow(mut self) -> Self {
+        self.reflow = true;
+        self
+    }
+
+    /// Align the continuation lines of list items after their marker, for the markers in
+    /// `LIST_MARKERS`
+    ///
+    /// ```rust
+    /// use indenter::wrap;
+    ///
+    /// let text = "- a bullet that wraps\n12. and a number";
+    ///
+    /// assert_eq!(
+    ///     wrap(text, 10).hang_list_markers().to_string(),
+    ///     "- a bullet\n  that\n  wraps\n12. and a\n    number"
+    /// );
+    /// ```
+    pub fn hang_list_markers(self) -> Self {
+        self.with_list_markers(LIST_MARKERS)
+    }
+
+    /// Align the continuation lines of list items after their marker, for markers matching one of
+    /// `markers`, in which `#` stands for a run of digits
+    ///
+    /// Leading whitespace before a marker is ignored. When reflowing, a line that starts with a
+    /// marker starts an item of its own instead of being joined to the line before it.
+    pub fn with_list_markers(mut self, markers: &'a [&'a str]) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    /// Returns the state for a line of output starting with `line`, hanging continuation lines
+    /// after its list marker if it has one
+    fn start(&self, line: &str, column: usize) -> Line {
+        let hang = marker_len(line.trim_start(), self.markers)
+            .map(|len| line.trim_start()[..len].chars().count())
+            .unwrap_or(0);
+
+        Line {
+            column,
+            first: true,
+            hang,
+        }
+    }
+
+    /// Writes the words of `line`, breaking lines as needed
+    fn words(&self, f: &mut fmt::Formatter<'_>, line: &str, state: &mut Line) -> fmt::Result {
+        for word in line.split_whitespace() {
+            let len = word.chars().count();
+
+            if state.first {
+                state.first = false;
+            } else if state.column + 1 + len > self.width {
+                f.write_char('\n')?;
+                write_spaces(f, state.hang)?;
+                state.column = state.hang;
+            } else {
+                f.write_char(' ')?;
+                state.column += 1;
+            }
+
+            f.write_str(word)?;
+            state.column += len;
+        }
+
+        Ok(())
+    }
+}
+
+/// Position within the output line being filled
+struct Line {
+    column: usize,
+    /// Whether no word has been written on this line of input, or paragraph when reflowing, yet
+    first: bool,
+    /// The indentation of continuation lines, the width of the list marker the line starts with
+    hang: usize,
+}
+
+impl fmt::Display for Wrapped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.reflow {
+            for (i, line) in self.text.split('\n').enumerate() {
+                let mut state = self.start(line, self.column);
+                if i > 0 {
+                    f.write_char('\n')?;
+                    state.column = 0;
+                }
+
+                self.words(f, line, &mut state)?;
+            }
+
+            return Ok(());
+        }
+
+        let mut state = Line {
+            column: self.column,
+            first: true,
+            hang: 0,
+        };
+        let mut blank = false;
+        for line in self.text.split('\n') {
+            if line.trim().is_empty() {
+                blank = true;
+                continue;
+            }
+
+            let item = marker_len(line.trim_start(), self.markers).is_some();
+            if (blank || item) && !state.first {
+                f.write_str(if blank { "\n\n" } else { "\n" })?;
+                state = self.start(line, 0);
+            } else if state.first {
+                state = self.start(line, state.column);
+            }
+
+            blank = false;
+            self.words(f, line, &mut state)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn long_words_overflow() {
+        assert_eq!(wrap("a bbbbbb c", 3).to_string(), "a\nbbbbbb\nc");
+    }
+
+    #[test]
+    fn keeps_line_breaks() {
+        assert_eq!(
+            wrap("one  two three\n\nfour", 9).to_string(),
+            "one two\nthree\n\nfour"
+        );
+    }
+
+    #[test]
+    fn reflow_drops_surrounding_blank_lines() {
+        assert_eq!(
+            reflow("\n  one\n two  \n\n three\n\n", 80).to_string(),
+            "one two\n\nthree"
+        );
+    }
+
+    #[test]
+    fn reflow_list_items() {
+        let text = "intro\n- first item\ncontinued\n  - nested";
+
+        assert_eq!(
+            reflow(text, 10).hang_list_markers().to_string(),
+            "intro\n- first\n  item\n  continued\n- nested"
+        );
+    }
+
+    #[test]
+    fn start_column() {
+        assert_eq!(wrap("ab cd", 5).start_column(2).to_string(), "ab\ncd");
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{Indent, Indentation};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// A writer for XML and HTML elements that indents them by their nesting
+///
+/// # Explanation
+///
+/// Elements are written one call at a time and closed in reverse order by `end_element`, which
+/// remembers the names of the open elements. By default every element that contains other
+/// elements is pretty printed, with its children on their own lines indented by two spaces per
+/// level, while elements that only contain text stay on one line. `compact` turns the line
+/// breaks off entirely. Text and attribute values are escaped.
+///
+/// ```rust
+/// use indenter::XmlWriter;
+///
+/// let mut w = XmlWriter::new(String::new());
+/// w.start_element("report")?;
+/// w.attribute("version", 1)?;
+/// w.start_element("title")?;
+/// w.text("Errors & warnings")?;
+/// w.end_element()?;
+/// w.start_element("empty")?;
+/// w.end_element()?;
+/// w.end_element()?;
+///
+/// assert_eq!(
+///     w.into_inner(),
+///     "<report version=\"1\">\n  <title>Errors &amp; warnings</title>\n  <empty/>\n</report>"
+/// );
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct XmlWriter<'a, W> {
+    inner: W,
+    unit: Option<&'static str>,
+    /// The open elements and whether each of them has element children so far
+    open: Vec<(&'a str, bool)>,
+    tag_open: bool,
+    started: bool,
+}
+
+impl<'a, W: fmt::Write> XmlWriter<'a, W> {
+    /// Construct a writer that indents nested elements with two spaces
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            unit: Some("  "),
+            open: Vec::new(),
+            tag_open: false,
+            started: false,
+        }
+    }
+
+    /// Sets the string repeated once per level of nesting
+    pub fn with_str(mut self, unit: &'static str) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Write all elements on a single line
+    pub fn compact(mut self) -> Self {
+        self.unit = None;
+        self
+    }
+
+    /// The number of elements currently open
+    pub fn depth(&self) -> Indent {
+        Indent(self.open.len())
+    }
+
+    /// Open an element called `name`
+    pub fn start_element(&mut self, name: &'a str) -> fmt::Result {
+        self.close_tag()?;
+
+        if let Some(parent) = self.open.last_mut() {
+            parent.1 = true;
+        }
+
+        if self.started {
+            self.newline()?;
+        }
+
+        self.started = true;
+        write!(self.inner, "<{}", name)?;
+        self.open.push((name, false));
+        self.tag_open = true;
+        Ok(())
+    }
+
+    /// Add an attribute to the element that was just opened
+    ///
+    /// Returns an error if anything has been written into the element already.
+    pub fn attribute(&mut self, name: &str, value: impl fmt::Display) -> fmt::Result {
+        if !self.tag_open {
+            return Err(fmt::Error);
+        }
+
+        write!(self.inner, " {}=\"", name)?;
+        write!(
+            Escape {
+                inner: &mut self.inner,
+                quotes: true,
+            },
+            "{}",
+            value
+        )?;
+        self.inner.write_char('"')
+    }
+
+    /// Write `text` into the current element, escaping markup characters
+    pub fn text(&mut self, text: impl fmt::Display) -> fmt::Result {
+        self.close_tag()?;
+        self.started = true;
+        write!(
+            Escape {
+                inner: &mut self.inner,
+                quotes: false,
+            },
+            "{}",
+            text
+        )
+    }
+
+    /// Close the innermost open element
+    ///
+    /// Elements without any content are written as self closing tags. Returns an error if no
+    /// element is open.
+    pub fn end_element(&mut self) -> fmt::Result {
+        let (name, children) = self.open.pop().ok_or(fmt::Error)?;
+
+        if self.tag_open {
+            self.tag_open = false;
+            return self.inner.write_str("/>");
+        }
+
+        if children {
+            self.newline()?;
+        }
+
+        write!(self.inner, "</{}>", name)
+    }
+
+    /// Close every element that is still open and return the underlying writer
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        while !self.open.is_empty() {
+            self.end_element()?;
+        }
+
+        Ok(self.inner)
+    }
+
+    /// Consumes this writer, returning the underlying writer without closing open elements
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn close_tag(&mut self) -> fmt::Result {
+        if self.tag_open {
+            self.tag_open = false;
+            self.inner.write_char('>')?;
+        }
+
+        Ok(())
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        match self.unit {
+            Some(unit) => write!(self.inner, "\n{}", Indentation::new(self.depth(), unit)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Escapes markup characters, and quotes if writing an attribute value
+struct Escape<'w, W> {
+    inner: &'w mut W,
+    quotes: bool,
+}
+
+impl<W: fmt::Write> fmt::Write for Escape<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+
+        while let Some(pos) = rest.find(['&', '<', '>', '"']) {
+            let (plain, tail) = rest.split_at(pos);
+            self.inner.write_str(plain)?;
+
+            let entity = match tail.as_bytes()[0] {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                _ if self.quotes => "&quot;",
+                _ => "\"",
+            };
+            self.inner.write_str(entity)?;
+            rest = &tail[1..];
+        }
+
+        self.inner.write_str(rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn compact_with_escaping() {
+        let mut w = XmlWriter::new(String::new()).compact();
+        w.start_element("a").unwrap();
+        w.attribute("title", "\"<x>\"").unwrap();
+        w.start_element("b").unwrap();
+        w.text("1 < 2").unwrap();
+
+        assert_eq!(
+            w.finish().unwrap(),
+            "<a title=\"&quot;&lt;x&gt;&quot;\"><b>1 &lt; 2</b></a>"
+        );
+    }
+
+    #[test]
+    fn nested_pretty() {
+        let mut w = XmlWriter::new(String::new()).with_str("\t");
+        w.start_element("a").unwrap();
+        w.start_element("b").unwrap();
+        w.start_element("c").unwrap();
+
+        assert_eq!(w.finish().unwrap(), "<a>\n\t<b>\n\t\t<c/>\n\t</b>\n</a>");
+    }
+
+    #[test]
+    fn misuse_is_an_error() {
+        let mut w = XmlWriter::new(String::new());
+        assert!(w.end_element().is_err());
+
+        w.start_element("a").unwrap();
+        w.text("x").unwrap();
+        assert!(w.attribute("late", 1).is_err());
+    }
+}